@@ -0,0 +1,280 @@
+use crate::{determine_round_winner, RoundOutcome, Shape};
+
+const TOLERANCE: f64 = 1e-9;
+
+/// Probabilities of playing rock, paper and scissors, in that order.
+pub type MixedStrategy = [f64; 3];
+
+pub struct ScoringTable {
+    /// Values of rock, paper and scissors.
+    pub shape_values: [f64; 3],
+    /// Values of a lost, tied and won round.
+    pub outcome_values: [f64; 3],
+}
+
+pub struct Equilibrium {
+    pub player_strategy: MixedStrategy,
+    pub opponent_strategy: MixedStrategy,
+    pub player_expected_score: f64,
+    pub opponent_expected_score: f64,
+}
+
+pub fn build_payoff_matrix(scoring_table: &ScoringTable) -> [[f64; 3]; 3] {
+    /*!
+     * Build the matrix of player scores, with a row for each player shape and
+     * a column for each opponent shape, using the round outcomes decided by
+     * `determine_round_winner`.
+     */
+
+    let mut payoff_matrix = [[0.0; 3]; 3];
+
+    for (player_idx, player_shape) in Shape::ALL.iter().enumerate() {
+        for (opponent_idx, opponent_shape) in Shape::ALL.iter().enumerate() {
+            let outcome_idx = match determine_round_winner((opponent_shape, player_shape)) {
+                RoundOutcome::OpponentWon => 0,
+                RoundOutcome::Tie => 1,
+                RoundOutcome::PlayerWon => 2,
+            };
+
+            payoff_matrix[player_idx][opponent_idx] =
+                scoring_table.shape_values[player_idx] + scoring_table.outcome_values[outcome_idx];
+        }
+    }
+
+    return payoff_matrix;
+}
+
+pub fn get_expected_score(
+    payoff_matrix: &[[f64; 3]; 3],
+    player_strategy: &MixedStrategy,
+    opponent_strategy: &MixedStrategy,
+) -> f64 {
+    /*!
+     * Expected player score per round when both sides play the given mixed
+     * strategies. Pure strategies are mixed strategies with a single 1.0.
+     */
+
+    let mut expected_score = 0.0;
+
+    for player_idx in 0..Shape::ALL.len() {
+        for opponent_idx in 0..Shape::ALL.len() {
+            expected_score += player_strategy[player_idx]
+                * opponent_strategy[opponent_idx]
+                * payoff_matrix[player_idx][opponent_idx];
+        }
+    }
+
+    return expected_score;
+}
+
+pub fn get_empirical_distribution(opponent_shapes: &[Shape]) -> MixedStrategy {
+    /*!
+     * The share of rounds in which each shape was played.
+     */
+
+    let mut distribution = [0.0; 3];
+
+    for shape in opponent_shapes {
+        distribution[shape.index()] += 1.0;
+    }
+
+    let num_rounds = opponent_shapes.len().max(1) as f64;
+
+    return distribution.map(|count| count / num_rounds);
+}
+
+pub fn find_nash_equilibria(payoff_matrix: &[[f64; 3]; 3]) -> Vec<Equilibrium> {
+    /*!
+     * Find the Nash equilibria of the game in which both sides are scored
+     * with the same payoff matrix, by support enumeration: for each pair of
+     * equally sized sets of shapes, solve for the mixes which make the other
+     * side indifferent between the shapes in its set, and keep the solutions
+     * in which no shape outside the set would do better.
+     */
+
+    // the opponent's score when the player plays row i and the opponent
+    // column j is the player's score with the roles swapped
+    let mut opponent_payoff_matrix = [[0.0; 3]; 3];
+    for (player_idx, row) in opponent_payoff_matrix.iter_mut().enumerate() {
+        for (opponent_idx, payoff) in row.iter_mut().enumerate() {
+            *payoff = payoff_matrix[opponent_idx][player_idx];
+        }
+    }
+
+    let mut equilibria: Vec<Equilibrium> = Vec::new();
+
+    for support_size in 1..=Shape::ALL.len() {
+        let supports = get_supports(support_size);
+
+        for player_support in &supports {
+            for opponent_support in &supports {
+                // the opponent's mix has to make the player indifferent
+                let opponent_strategy = match solve_indifference(
+                    |player_idx, opponent_idx| payoff_matrix[player_idx][opponent_idx],
+                    player_support,
+                    opponent_support,
+                ) {
+                    Some(strategy) => strategy,
+                    None => continue,
+                };
+                // and the player's mix has to make the opponent indifferent
+                let player_strategy = match solve_indifference(
+                    |opponent_idx, player_idx| opponent_payoff_matrix[player_idx][opponent_idx],
+                    opponent_support,
+                    player_support,
+                ) {
+                    Some(strategy) => strategy,
+                    None => continue,
+                };
+
+                let player_expected_score =
+                    get_expected_score(payoff_matrix, &player_strategy, &opponent_strategy);
+                let opponent_expected_score =
+                    get_expected_score(payoff_matrix, &opponent_strategy, &player_strategy);
+
+                let has_better_player_response = (0..Shape::ALL.len()).any(|player_idx| {
+                    let pure = get_pure_strategy(player_idx);
+                    get_expected_score(payoff_matrix, &pure, &opponent_strategy)
+                        > player_expected_score + TOLERANCE
+                });
+                let has_better_opponent_response = (0..Shape::ALL.len()).any(|opponent_idx| {
+                    let pure = get_pure_strategy(opponent_idx);
+                    get_expected_score(payoff_matrix, &pure, &player_strategy)
+                        > opponent_expected_score + TOLERANCE
+                });
+                if has_better_player_response || has_better_opponent_response {
+                    continue;
+                }
+
+                let is_duplicate = equilibria.iter().any(|equilibrium| {
+                    are_strategies_equal(&equilibrium.player_strategy, &player_strategy)
+                        && are_strategies_equal(&equilibrium.opponent_strategy, &opponent_strategy)
+                });
+                if !is_duplicate {
+                    equilibria.push(Equilibrium {
+                        player_strategy,
+                        opponent_strategy,
+                        player_expected_score,
+                        opponent_expected_score,
+                    });
+                }
+            }
+        }
+    }
+
+    return equilibria;
+}
+
+pub fn get_pure_strategy(shape_idx: usize) -> MixedStrategy {
+    let mut strategy = [0.0; 3];
+    strategy[shape_idx] = 1.0;
+
+    return strategy;
+}
+
+fn are_strategies_equal(a: &MixedStrategy, b: &MixedStrategy) -> bool {
+    return a.iter().zip(b.iter()).all(|(x, y)| (x - y).abs() < 1e-6);
+}
+
+fn get_supports(support_size: usize) -> Vec<Vec<usize>> {
+    /*!
+     * All sets of `support_size` shape indices.
+     */
+
+    return (0u32..(1 << Shape::ALL.len()))
+        .filter(|mask| mask.count_ones() as usize == support_size)
+        .map(|mask| {
+            (0..Shape::ALL.len())
+                .filter(|idx| mask & (1 << idx) != 0)
+                .collect()
+        })
+        .collect();
+}
+
+fn solve_indifference<F>(
+    payoff: F,
+    indifferent_support: &[usize],
+    mixing_support: &[usize],
+) -> Option<MixedStrategy>
+where
+    F: Fn(usize, usize) -> f64,
+{
+    /*!
+     * Find the mix over `mixing_support` which gives every shape in
+     * `indifferent_support` the same payoff, where `payoff(i, j)` is the
+     * payoff of the indifferent side playing i against the mixing side
+     * playing j. Returns None if there is no unique non-negative solution.
+     *
+     * The unknowns are the probabilities of the shapes in `mixing_support`
+     * followed by the common payoff.
+     */
+
+    let num_unknowns = mixing_support.len() + 1;
+    let mut system: Vec<Vec<f64>> = Vec::with_capacity(num_unknowns);
+
+    for indifferent_idx in indifferent_support {
+        let mut row: Vec<f64> = mixing_support
+            .iter()
+            .map(|mixing_idx| payoff(*indifferent_idx, *mixing_idx))
+            .collect();
+        row.push(-1.0);
+        row.push(0.0);
+        system.push(row);
+    }
+
+    let mut probabilities_sum = vec![1.0; mixing_support.len()];
+    probabilities_sum.push(0.0);
+    probabilities_sum.push(1.0);
+    system.push(probabilities_sum);
+
+    let solution = solve_linear_system(system)?;
+
+    let mut strategy = [0.0; 3];
+    for (mixing_idx, probability) in mixing_support.iter().zip(solution.iter()) {
+        if *probability < -TOLERANCE {
+            return None;
+        }
+        strategy[*mixing_idx] = probability.max(0.0);
+    }
+
+    return Some(strategy);
+}
+
+fn solve_linear_system(mut system: Vec<Vec<f64>>) -> Option<Vec<f64>> {
+    /*!
+     * Solve a square linear system given as rows of coefficients followed
+     * by the constant term, using Gaussian elimination with partial pivoting.
+     */
+
+    let size = system.len();
+
+    for column in 0..size {
+        let pivot_row = (column..size).max_by(|a, b| {
+            system[*a][column]
+                .abs()
+                .total_cmp(&system[*b][column].abs())
+        })?;
+        if system[pivot_row][column].abs() < TOLERANCE {
+            return None;
+        }
+        system.swap(column, pivot_row);
+
+        let pivot = system[column].clone();
+        for (row_idx, row) in system.iter_mut().enumerate() {
+            if row_idx == column {
+                continue;
+            }
+
+            let factor = row[column] / pivot[column];
+            for (entry, pivot_entry) in row.iter_mut().zip(pivot.iter()).skip(column) {
+                *entry -= factor * pivot_entry;
+            }
+        }
+    }
+
+    return Some(
+        (0..size)
+            .map(|row| system[row][size] / system[row][row])
+            .collect(),
+    );
+}
@@ -0,0 +1,207 @@
+use crate::{RoundOutcome, Shape};
+
+const CANDIDATE_DELIMITERS: [u8; 4] = [b',', b';', b'\t', b' '];
+
+#[derive(Debug)]
+pub enum GuideEncoding {
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+}
+
+pub struct GuideIssue {
+    pub line_number: u64,
+    pub content: String,
+    pub reason: String,
+}
+
+pub struct ParsedGuide {
+    pub rounds: Vec<(Shape, RoundOutcome)>,
+    pub issues: Vec<GuideIssue>,
+    pub delimiter: u8,
+    pub encoding: GuideEncoding,
+}
+
+pub fn parse_strategy_guide(raw_guide: &[u8]) -> Result<ParsedGuide, String> {
+    /*!
+     * Parse a strategy guide, detecting its text encoding and its delimiter
+     * (comma-separated, or space-separated as in the original puzzle input).
+     * Rows which cannot be used are not dropped silently, but returned as
+     * issues together with their line numbers.
+     */
+
+    let (guide_text, encoding) = decode_guide(raw_guide)?;
+    let delimiter = detect_delimiter(&guide_text);
+
+    let mut rounds: Vec<(Shape, RoundOutcome)> = Vec::new();
+    let mut issues: Vec<GuideIssue> = Vec::new();
+
+    // the csv reader skips blank lines without counting them, so each line is
+    // read on its own to keep the line numbers of the file
+    for (line_idx, line) in guide_text.lines().enumerate() {
+        let line_number = line_idx as u64 + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut csv_reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .delimiter(delimiter)
+            .trim(csv::Trim::All)
+            .from_reader(line.as_bytes());
+
+        let entry = match csv_reader.records().next() {
+            Some(Ok(entry)) => entry,
+            Some(Err(error)) => {
+                issues.push(GuideIssue {
+                    line_number,
+                    content: line.trim().to_string(),
+                    reason: format!("unreadable row: {error}"),
+                });
+                continue;
+            }
+            None => continue,
+        };
+
+        // repeated spaces in space-separated guides produce empty fields
+        let fields: Vec<&str> = if delimiter == b' ' {
+            entry.iter().filter(|field| !field.is_empty()).collect()
+        } else {
+            entry.iter().collect()
+        };
+        let content = fields.join(&(delimiter as char).to_string());
+
+        let mut report_issue = |reason: String| {
+            issues.push(GuideIssue {
+                line_number,
+                content: content.clone(),
+                reason,
+            });
+        };
+
+        let (opponent_move, player_outcome) = match fields.as_slice() {
+            [opponent_move, player_outcome] => (*opponent_move, *player_outcome),
+            [] | [""] => continue,
+            _ => {
+                report_issue(format!("expected two fields, found {}", fields.len()));
+                continue;
+            }
+        };
+
+        let opponent_shape = match opponent_move {
+            "A" => Shape::Rock,
+            "B" => Shape::Paper,
+            "C" => Shape::Scissors,
+            _ => {
+                report_issue(format!("unknown move for opponent: {opponent_move}"));
+                continue;
+            }
+        };
+        let target_outcome = match player_outcome {
+            "X" => RoundOutcome::OpponentWon,
+            "Y" => RoundOutcome::Tie,
+            "Z" => RoundOutcome::PlayerWon,
+            _ => {
+                report_issue(format!("unknown move for player: {player_outcome}"));
+                continue;
+            }
+        };
+
+        rounds.push((opponent_shape, target_outcome));
+    }
+
+    return Ok(ParsedGuide {
+        rounds,
+        issues,
+        delimiter,
+        encoding,
+    });
+}
+
+fn decode_guide(raw_guide: &[u8]) -> Result<(String, GuideEncoding), String> {
+    /*!
+     * Decode the guide based on its byte order mark, assuming UTF-8 when
+     * there is none.
+     */
+
+    let decode_utf16 = |bytes: &[u8], to_u16: fn([u8; 2]) -> u16| {
+        let units: Vec<u16> = bytes
+            .chunks(2)
+            .map(|pair| to_u16([pair[0], *pair.get(1).unwrap_or(&0)]))
+            .collect();
+        return String::from_utf16(&units)
+            .map_err(|_| String::from("The guide is not valid UTF-16."));
+    };
+
+    let decoded = match raw_guide {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => (
+            String::from_utf8(rest.to_vec())
+                .map_err(|error| format!("The guide is not valid UTF-8: {error}."))?,
+            GuideEncoding::Utf8Bom,
+        ),
+        [0xFF, 0xFE, rest @ ..] => (
+            decode_utf16(rest, u16::from_le_bytes)?,
+            GuideEncoding::Utf16Le,
+        ),
+        [0xFE, 0xFF, rest @ ..] => (
+            decode_utf16(rest, u16::from_be_bytes)?,
+            GuideEncoding::Utf16Be,
+        ),
+        _ => (
+            String::from_utf8(raw_guide.to_vec())
+                .map_err(|error| format!("The guide is not valid UTF-8: {error}."))?,
+            GuideEncoding::Utf8,
+        ),
+    };
+
+    return Ok(decoded);
+}
+
+fn detect_delimiter(guide_text: &str) -> u8 {
+    /*!
+     * Pick the candidate delimiter which appears on the most non-empty lines.
+     * Commas win ties, as that is the format the guides are normally in.
+     */
+
+    let non_empty_lines: Vec<&str> = guide_text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    let mut best_delimiter = CANDIDATE_DELIMITERS[0];
+    let mut best_count = 0;
+
+    for delimiter in CANDIDATE_DELIMITERS {
+        let count = non_empty_lines
+            .iter()
+            .filter(|line| line.as_bytes().contains(&delimiter))
+            .count();
+
+        if count > best_count {
+            best_delimiter = delimiter;
+            best_count = count;
+        }
+    }
+
+    return best_delimiter;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issues_keep_line_numbers_after_blank_lines() {
+        let guide = parse_strategy_guide(b"A,Y\n\n\nB,Q").unwrap();
+        let line_numbers: Vec<u64> = guide.issues.iter().map(|issue| issue.line_number).collect();
+        assert_eq!(guide.rounds.len(), 1);
+        assert_eq!(line_numbers, vec![4]);
+
+        let guide = parse_strategy_guide(b"A Y\n\nQ Y\n\n\nA\n").unwrap();
+        let line_numbers: Vec<u64> = guide.issues.iter().map(|issue| issue.line_number).collect();
+        assert_eq!(line_numbers, vec![3, 6]);
+    }
+}
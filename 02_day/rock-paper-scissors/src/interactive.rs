@@ -0,0 +1,97 @@
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use crate::strategies::PlayerStrategy;
+use crate::{determine_round_winner, get_outcome_value, get_single_shape_value};
+use crate::{encode_opponent_shape, encode_round_outcome, RoundOutcome, Shape};
+
+pub fn play_session<P>(bot: &mut dyn PlayerStrategy, log_file: P) -> io::Result<()>
+where
+    P: AsRef<Path>,
+{
+    /*!
+     * Play rounds against the given bot, reading the human player's shapes
+     * from stdin until `q` or the end of input. Each round is scored as it is
+     * played, and the session is saved to `log_file` in the strategy guide
+     * format, so it can be re-scored later.
+     */
+
+    let mut session: Vec<(Shape, RoundOutcome)> = Vec::new();
+    let mut total_score = 0;
+
+    let stdin = io::stdin();
+    let mut input_lines = stdin.lock().lines();
+
+    loop {
+        print!("Your move ([r]ock, [p]aper, [s]cissors, [q]uit): ");
+        io::stdout().flush()?;
+
+        let line = match input_lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
+
+        let player_shape = match line.trim().to_lowercase().as_str() {
+            "r" | "rock" => Shape::Rock,
+            "p" | "paper" => Shape::Paper,
+            "s" | "scissors" => Shape::Scissors,
+            "q" | "quit" => break,
+            "" => continue,
+            unknown => {
+                println!("Unknown move: {unknown}.");
+                continue;
+            }
+        };
+
+        let bot_shape = bot.choose_shape();
+        bot.observe(bot_shape, player_shape);
+
+        let outcome = determine_round_winner((&bot_shape, &player_shape));
+        let shape_score = get_single_shape_value(&player_shape);
+        let outcome_score = get_outcome_value(&outcome);
+        total_score += shape_score + outcome_score;
+
+        println!(
+            "{} played {bot_shape:?}: {outcome:?} (shape {shape_score} + outcome {outcome_score}), total {total_score}",
+            bot.name()
+        );
+
+        session.push((bot_shape, outcome));
+    }
+
+    println!();
+    println!(
+        "Played {} rounds, total score {total_score}.",
+        session.len()
+    );
+
+    save_session(&session, &log_file)?;
+    println!("Session saved to {}.", log_file.as_ref().display());
+
+    return Ok(());
+}
+
+fn save_session<P>(session: &[(Shape, RoundOutcome)], log_file: P) -> io::Result<()>
+where
+    P: AsRef<Path>,
+{
+    /*!
+     * Write the played rounds as opponent shape and round outcome letters,
+     * which is the format `load_strategy_guide` reads.
+     */
+
+    let mut csv_writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_path(log_file)?;
+
+    for (bot_shape, outcome) in session {
+        csv_writer.write_record([
+            encode_opponent_shape(bot_shape),
+            encode_round_outcome(outcome),
+        ])?;
+    }
+
+    csv_writer.flush()?;
+
+    return Ok(());
+}
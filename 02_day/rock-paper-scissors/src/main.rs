@@ -1,84 +1,530 @@
-use std::collections::HashMap;
-use std::path::Path;
+#![allow(clippy::needless_return)]
+
+mod analysis;
+mod guide_parser;
+mod interactive;
+mod solver;
+mod strategies;
+mod tournament;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use analysis::ScoringTable;
+use guide_parser::{GuideIssue, ParsedGuide};
+use solver::SolverConstraints;
+use strategies::{
+    FixedSequence, FrequencyCounter, MarkovPredictor, PlayerStrategy, RandomStrategy,
+};
+use tournament::StrategyFactory;
 
 fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
     let current_file = Path::new(file!());
 
-    let data_file = current_file
+    let data_dir = current_file
         .parent()
         .unwrap()
         .parent()
         .unwrap()
-        .join("data/data.csv");
-    let strategy_guide = load_strategy_guide(data_file);
+        .join("data");
+
+    if args.first().map(String::as_str) == Some("play") {
+        run_interactive_session(&args[1..], &data_dir);
+        return;
+    }
+
+    let data_file = match get_option_value(&args, "--guide") {
+        Some(guide_file) => PathBuf::from(guide_file),
+        None => data_dir.join("data.csv"),
+    };
+    let parsed_guide = match load_strategy_guide(&data_file) {
+        Ok(parsed_guide) => parsed_guide,
+        Err(message) => {
+            eprintln!("{}: {message}", data_file.display());
+            std::process::exit(1);
+        }
+    };
+    let strict = args.iter().any(|arg| arg == "--strict");
 
-    let total_player_score = calculate_total_score(strategy_guide);
+    if args.first().map(String::as_str) == Some("lint") {
+        run_lint(&parsed_guide, strict);
+        return;
+    }
+
+    if !parsed_guide.issues.is_empty() {
+        if strict {
+            print_guide_issues(&parsed_guide.issues);
+            eprintln!("The strategy guide has rows which had to be skipped.");
+            std::process::exit(1);
+        }
+
+        eprintln!(
+            "Skipped {} rows of the strategy guide, run `lint` for details.",
+            parsed_guide.issues.len()
+        );
+    }
+
+    let strategy_guide = parsed_guide.rounds;
+
+    match args.first().map(String::as_str) {
+        Some("optimise") => {
+            run_optimiser(&strategy_guide, &args[1..]);
+            return;
+        }
+        Some("tournament") => {
+            run_tournament(&strategy_guide, &args[1..]);
+            return;
+        }
+        Some("analyse") => {
+            run_analysis(&strategy_guide, &args[1..]);
+            return;
+        }
+        _ => {}
+    }
+
+    if args.iter().any(|arg| arg == "--rounds") {
+        print_round_breakdown(&strategy_guide);
+    }
+
+    let total_player_score = calculate_total_score(&strategy_guide);
 
     println!("The total player score is: {total_player_score}.");
 }
 
-fn load_strategy_guide<P>(file: P) -> HashMap<u32, (Shape, RoundOutcome)>
+fn get_option_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    /*!
+     * Fetch the value following the given flag in the command line arguments,
+     * e.g. the `3` in `--max-wins 3`.
+     */
+
+    let flag_idx = args.iter().position(|arg| arg == flag)?;
+
+    return args.get(flag_idx + 1).map(String::as_str);
+}
+
+fn parse_count_option(args: &[String], flag: &str) -> Option<usize> {
+    /*!
+     * Parse the value of a flag taking a non-negative integer.
+     */
+
+    return get_option_value(args, flag).map(|value| {
+        value
+            .parse::<usize>()
+            .unwrap_or_else(|_| panic!("Invalid value for {flag}: {value}."))
+    });
+}
+
+fn run_optimiser(strategy_guide: &[(Shape, RoundOutcome)], args: &[String]) {
+    /*!
+     * Find the best possible player moves against the opponent column of the
+     * strategy guide, and report how the elf's guide compares to them.
+     */
+
+    let constraints = SolverConstraints {
+        max_wins: parse_count_option(args, "--max-wins"),
+        max_repeat: parse_count_option(args, "--max-repeat"),
+    };
+
+    let opponent_shapes: Vec<Shape> = strategy_guide.iter().map(|round| round.0).collect();
+
+    let solution = match solver::find_optimal_moves(&opponent_shapes, &constraints) {
+        Ok(solution) => solution,
+        Err(message) => {
+            eprintln!("{message}");
+            std::process::exit(1);
+        }
+    };
+
+    let guide_score = calculate_total_score(strategy_guide);
+    let guide_shapes: Vec<Shape> = strategy_guide
+        .iter()
+        .map(|(opponent_shape, target_outcome)| choose_player_shape(opponent_shape, target_outcome))
+        .collect();
+    let differing_moves = guide_shapes
+        .iter()
+        .zip(solution.player_shapes.iter())
+        .filter(|(guide_shape, optimal_shape)| guide_shape != optimal_shape)
+        .count();
+
+    println!(
+        "The optimal total score is: {} ({} rounds won).",
+        solution.total_score, solution.wins
+    );
+    println!("The strategy guide total score is: {guide_score}.");
+
+    let violations =
+        solver::find_constraint_violations(&opponent_shapes, &guide_shapes, &constraints);
+    if !violations.is_empty() {
+        println!(
+            "The guide does not meet the constraints: {}.",
+            violations.join(", ")
+        );
+    }
+
+    let comparison = if guide_score > solution.total_score {
+        format!(
+            "{} points above the constrained optimum",
+            guide_score - solution.total_score
+        )
+    } else {
+        format!(
+            "{} points short of optimal",
+            solution.total_score - guide_score
+        )
+    };
+    println!(
+        "The guide is {comparison}, with {differing_moves} of {} moves differing.",
+        strategy_guide.len()
+    );
+}
+
+fn parse_seed_option(args: &[String]) -> u64 {
+    /*!
+     * Parse the `--seed` flag used to make simulations reproducible.
+     */
+
+    return get_option_value(args, "--seed")
+        .map(|value| {
+            value
+                .parse::<u64>()
+                .unwrap_or_else(|_| panic!("Invalid value for --seed: {value}."))
+        })
+        .unwrap_or(2022);
+}
+
+fn run_interactive_session(args: &[String], data_dir: &Path) {
+    /*!
+     * Let a human play against one of the bots from the terminal, saving the
+     * session to `--log` (by default `data/session.csv`).
+     */
+
+    let seed = parse_seed_option(args);
+
+    let mut bot: Box<dyn PlayerStrategy> = match get_option_value(args, "--bot").unwrap_or("markov")
+    {
+        "random" => Box::new(RandomStrategy::new(seed)),
+        "frequency" => Box::new(FrequencyCounter::new(seed)),
+        "markov" => Box::new(MarkovPredictor::new(seed)),
+        unknown => panic!("Unknown bot: {unknown}."),
+    };
+
+    let log_file = match get_option_value(args, "--log") {
+        Some(log_file) => PathBuf::from(log_file),
+        None => data_dir.join("session.csv"),
+    };
+
+    interactive::play_session(bot.as_mut(), log_file).unwrap();
+}
+
+fn run_tournament(strategy_guide: &[(Shape, RoundOutcome)], args: &[String]) {
+    /*!
+     * Run a round-robin tournament between the built-in strategies, the
+     * elf's guide, the opponent column of the guide and a generated opponent
+     * sequence, and print the standings.
+     */
+
+    let seed = parse_seed_option(args);
+    let rounds_per_match = parse_count_option(args, "--rounds").unwrap_or(strategy_guide.len());
+    if rounds_per_match == 0 {
+        eprintln!("A tournament needs at least one round per match, set one with --rounds.");
+        std::process::exit(1);
+    }
+
+    let guide_shapes: Vec<Shape> = strategy_guide
+        .iter()
+        .map(|(opponent_shape, target_outcome)| choose_player_shape(opponent_shape, target_outcome))
+        .collect();
+    let opponent_shapes: Vec<Shape> = strategy_guide.iter().map(|round| round.0).collect();
+    let generated_shapes = strategies::generate_sticky_sequence(rounds_per_match, seed);
+
+    let mut participants: Vec<StrategyFactory> = Vec::new();
+    // an empty guide has no moves to replay, so it sits the tournament out
+    if strategy_guide.is_empty() {
+        eprintln!("The strategy guide has no rounds, so the guide and opponent do not take part.");
+    } else {
+        participants.push(Box::new(move |_seed| {
+            Box::new(FixedSequence::new("guide", guide_shapes.clone()))
+        }));
+        participants.push(Box::new(move |_seed| {
+            Box::new(FixedSequence::new("opponent", opponent_shapes.clone()))
+        }));
+    }
+    participants.push(Box::new(move |_seed| {
+        Box::new(FixedSequence::new("generated", generated_shapes.clone()))
+    }));
+    participants.push(Box::new(|seed| Box::new(RandomStrategy::new(seed))));
+    participants.push(Box::new(|seed| Box::new(FrequencyCounter::new(seed))));
+    participants.push(Box::new(|seed| Box::new(MarkovPredictor::new(seed))));
+
+    let mut standings = tournament::run_round_robin(&participants, rounds_per_match, seed);
+    standings.sort_by_key(|standing| std::cmp::Reverse(standing.total_score));
+
+    println!("Round-robin tournament, {rounds_per_match} rounds per match, seed {seed}:");
+    for standing in standings {
+        println!(
+            "{:<10} matches won {:>2}, rounds won/tied/lost {}/{}/{}, win rate {:.3}, average score {:.3}",
+            standing.name,
+            standing.matches_won,
+            standing.rounds_won,
+            standing.rounds_tied,
+            standing.rounds_lost,
+            standing.win_rate(),
+            standing.average_score()
+        );
+    }
+}
+
+fn parse_triple_option(args: &[String], flag: &str) -> Option<[f64; 3]> {
+    /*!
+     * Parse a flag taking three comma-separated numbers, e.g. the rock,
+     * paper and scissors values in `--shape-values 1,2,3`.
+     */
+
+    return get_option_value(args, flag).map(|value| {
+        let numbers: Vec<f64> = value
+            .split(',')
+            .map(|number| {
+                number
+                    .trim()
+                    .parse::<f64>()
+                    .unwrap_or_else(|_| panic!("Invalid value for {flag}: {value}."))
+            })
+            .collect();
+
+        return numbers
+            .try_into()
+            .unwrap_or_else(|_| panic!("Expected three values for {flag}: {value}."));
+    });
+}
+
+fn run_analysis(strategy_guide: &[(Shape, RoundOutcome)], args: &[String]) {
+    /*!
+     * Print the payoff matrix of the game, its Nash equilibria, and the
+     * expected score per round of the pure, equilibrium and (optionally) a
+     * given `--strategy` mix against the opponent's shape distribution in the
+     * strategy guide. The scoring table can be changed with `--shape-values`
+     * and `--outcome-values`.
+     */
+
+    let strategy = parse_triple_option(args, "--strategy").map(|weights| {
+        let total_weight: f64 = weights.iter().sum();
+        if weights
+            .iter()
+            .any(|weight| !weight.is_finite() || *weight < 0.0)
+            || total_weight <= 0.0
+        {
+            eprintln!(
+                "The --strategy weights must not be negative, and at least one must be positive."
+            );
+            std::process::exit(1);
+        }
+        return weights.map(|weight| weight / total_weight);
+    });
+
+    let default_shape_values = Shape::ALL.map(|shape| get_single_shape_value(&shape) as f64);
+    let default_outcome_values = [
+        RoundOutcome::OpponentWon,
+        RoundOutcome::Tie,
+        RoundOutcome::PlayerWon,
+    ]
+    .map(|outcome| get_outcome_value(&outcome) as f64);
+
+    let scoring_table = ScoringTable {
+        shape_values: parse_triple_option(args, "--shape-values").unwrap_or(default_shape_values),
+        outcome_values: parse_triple_option(args, "--outcome-values")
+            .unwrap_or(default_outcome_values),
+    };
+    let payoff_matrix = analysis::build_payoff_matrix(&scoring_table);
+
+    println!("Payoff matrix (player shape by opponent shape):");
+    println!("{:>10} {:>8} {:>8} {:>8}", "", "Rock", "Paper", "Scissors");
+    for (shape, row) in Shape::ALL.iter().zip(payoff_matrix.iter()) {
+        println!(
+            "{:>10} {:>8} {:>8} {:>8}",
+            format!("{shape:?}"),
+            row[0],
+            row[1],
+            row[2]
+        );
+    }
+
+    println!();
+    println!("Nash equilibria (rock/paper/scissors probabilities):");
+    let equilibria = analysis::find_nash_equilibria(&payoff_matrix);
+    for equilibrium in &equilibria {
+        println!(
+            "player {}, opponent {}, expected scores {:.3} / {:.3}",
+            format_mixed_strategy(&equilibrium.player_strategy),
+            format_mixed_strategy(&equilibrium.opponent_strategy),
+            equilibrium.player_expected_score,
+            equilibrium.opponent_expected_score
+        );
+    }
+
+    let opponent_shapes: Vec<Shape> = strategy_guide.iter().map(|round| round.0).collect();
+    let opponent_distribution = analysis::get_empirical_distribution(&opponent_shapes);
+
+    println!();
+    println!(
+        "Expected score per round against the guide's opponent distribution {}:",
+        format_mixed_strategy(&opponent_distribution)
+    );
+    for (shape_idx, shape) in Shape::ALL.iter().enumerate() {
+        let expected_score = analysis::get_expected_score(
+            &payoff_matrix,
+            &analysis::get_pure_strategy(shape_idx),
+            &opponent_distribution,
+        );
+        println!("{:>12}: {expected_score:.3}", format!("always {shape:?}"));
+    }
+    for (equilibrium_idx, equilibrium) in equilibria.iter().enumerate() {
+        let expected_score = analysis::get_expected_score(
+            &payoff_matrix,
+            &equilibrium.player_strategy,
+            &opponent_distribution,
+        );
+        println!(
+            "{:>12}: {expected_score:.3}",
+            format!("equilibrium {}", equilibrium_idx + 1)
+        );
+    }
+    if let Some(strategy) = strategy {
+        let expected_score =
+            analysis::get_expected_score(&payoff_matrix, &strategy, &opponent_distribution);
+        println!(
+            "{:>12}: {expected_score:.3} (mix {})",
+            "--strategy",
+            format_mixed_strategy(&strategy)
+        );
+    }
+}
+
+fn format_mixed_strategy(strategy: &analysis::MixedStrategy) -> String {
+    return format!("{:.3}/{:.3}/{:.3}", strategy[0], strategy[1], strategy[2]);
+}
+
+fn load_strategy_guide<P>(file: P) -> Result<ParsedGuide, String>
 where
     P: AsRef<Path>,
 {
     /*!
-     * Load the given strategy guide from an external CSV file. Return a
-     * dictionary holding an u32 round ID, and tuples of Shapes for the
-     * opponent's choice and RoundOutcomes for the round outcome that has to
-     * be achieved.
-     */
-
-    let mut strategy_guide: HashMap<u32, (Shape, RoundOutcome)> = HashMap::new();
-
-    let mut csv_reader = csv::ReaderBuilder::new()
-        .has_headers(false)
-        .from_path(file)
-        .unwrap();
-
-    let mut round_id = 1;
-
-    for record in csv_reader.records() {
-        if let Ok(entry) = record {
-            if let (Some(opponent_move), Some(player_outcome)) = (entry.get(0), entry.get(1)) {
-                let opponent_shape = match opponent_move {
-                    "A" => Shape::Rock,
-                    "B" => Shape::Paper,
-                    "C" => Shape::Scissors,
-                    _ => panic!("Unkown move found for opponent: {opponent_move}."),
-                };
-                let target_outcome = match player_outcome {
-                    "X" => RoundOutcome::OpponentWon,
-                    "Y" => RoundOutcome::Tie,
-                    "Z" => RoundOutcome::PlayerWon,
-                    _ => panic!("Unkown move found for player: {player_outcome}."),
-                };
-
-                strategy_guide.insert(round_id, (opponent_shape, target_outcome));
-                round_id += 1;
-            }
-        }
+     * Load the given strategy guide from an external file. The parsed guide
+     * holds a vector of rounds, in the order in which they appear in the file,
+     * with tuples of Shapes for the opponent's choice and RoundOutcomes for
+     * the round outcome that has to be achieved, along with any rows which
+     * had to be skipped. Files which cannot be read or decoded are an error.
+     */
+
+    let raw_guide = fs::read(file).map_err(|error| error.to_string())?;
+
+    return guide_parser::parse_strategy_guide(&raw_guide);
+}
+
+fn print_guide_issues(issues: &[GuideIssue]) {
+    /*!
+     * Print each skipped or malformed row of the strategy guide.
+     */
+
+    for issue in issues {
+        eprintln!(
+            "line {}: {} ({:?})",
+            issue.line_number, issue.reason, issue.content
+        );
+    }
+}
+
+fn run_lint(parsed_guide: &ParsedGuide, strict: bool) {
+    /*!
+     * Report the detected format of the strategy guide and every row which
+     * could not be used. In strict mode, any such row fails the lint.
+     */
+
+    println!(
+        "Encoding: {:?}, delimiter: {:?}.",
+        parsed_guide.encoding, parsed_guide.delimiter as char
+    );
+    println!(
+        "{} valid rounds, {} skipped or malformed rows.",
+        parsed_guide.rounds.len(),
+        parsed_guide.issues.len()
+    );
+    print_guide_issues(&parsed_guide.issues);
+
+    if strict && !parsed_guide.issues.is_empty() {
+        std::process::exit(1);
     }
+}
+
+fn encode_opponent_shape(shape: &Shape) -> &'static str {
+    /*!
+     * The strategy guide letter for an opponent's shape.
+     */
+
+    let letter = match shape {
+        Shape::Rock => "A",
+        Shape::Paper => "B",
+        Shape::Scissors => "C",
+    };
+
+    return letter;
+}
+
+fn encode_round_outcome(round_outcome: &RoundOutcome) -> &'static str {
+    /*!
+     * The strategy guide letter for the outcome the player has to achieve.
+     */
+
+    let letter = match round_outcome {
+        RoundOutcome::OpponentWon => "X",
+        RoundOutcome::Tie => "Y",
+        RoundOutcome::PlayerWon => "Z",
+    };
 
-    return strategy_guide;
+    return letter;
 }
 
-fn calculate_total_score(strategy_guide: HashMap<u32, (Shape, RoundOutcome)>) -> u64 {
+fn calculate_total_score(strategy_guide: &[(Shape, RoundOutcome)]) -> u64 {
     /*!
      * Given a provided strategy, calculate the total player score for all rounds.
      */
-    let total_shape_score = get_total_shape_value(&strategy_guide);
-    let total_contest_score = get_total_contest_score(&strategy_guide);
+    let total_shape_score = get_total_shape_value(strategy_guide);
+    let total_contest_score = get_total_contest_score(strategy_guide);
 
     return total_shape_score + total_contest_score;
 }
 
-fn get_total_contest_score(strategy_guide: &HashMap<u32, (Shape, RoundOutcome)>) -> u64 {
+fn print_round_breakdown(strategy_guide: &[(Shape, RoundOutcome)]) {
+    /*!
+     * Print a line for each round of the strategy guide, showing the shapes
+     * played, the round outcome, the scores obtained for the shape and the
+     * outcome, and the running total after the round.
+     */
+
+    let mut running_total = 0;
+
+    for (round_idx, (opponent_shape, target_outcome)) in strategy_guide.iter().enumerate() {
+        let player_shape = choose_player_shape(opponent_shape, target_outcome);
+        let shape_score = get_single_shape_value(&player_shape);
+        let outcome_score = get_outcome_value(target_outcome);
+        running_total += shape_score + outcome_score;
+
+        println!(
+            "Round {}: opponent {opponent_shape:?}, player {player_shape:?}, {target_outcome:?} (shape {shape_score} + outcome {outcome_score}), running total {running_total}",
+            round_idx + 1
+        );
+    }
+}
+
+fn get_total_contest_score(strategy_guide: &[(Shape, RoundOutcome)]) -> u64 {
     /*!
      * Determines the total score of all the opponent vs player contests.
      */
 
     let total_contest_score = strategy_guide
-        .values()
+        .iter()
         .map(|strategy| get_outcome_value(&strategy.1))
         .sum();
 
@@ -99,7 +545,7 @@ fn get_outcome_value(round_outcome: &RoundOutcome) -> u64 {
     return value;
 }
 
-fn get_total_shape_value(strategy_guide: &HashMap<u32, (Shape, RoundOutcome)>) -> u64 {
+fn get_total_shape_value(strategy_guide: &[(Shape, RoundOutcome)]) -> u64 {
     /*!
      * Takes all the shapes the player's played in the strategy guide and
      * retunrs their total value. This is added to the scores obtained from
@@ -107,7 +553,7 @@ fn get_total_shape_value(strategy_guide: &HashMap<u32, (Shape, RoundOutcome)>) -
      */
 
     let total_shape_value = strategy_guide
-        .values()
+        .iter()
         .map(|strategy| {
             let player_shape = choose_player_shape(&strategy.0, &strategy.1);
             return get_single_shape_value(&player_shape);
@@ -152,6 +598,17 @@ fn determine_round_winner(round_choice_pair: (&Shape, &Shape)) -> RoundOutcome {
     return outcome;
 }
 
+fn get_round_score(opponent_shape: &Shape, player_shape: &Shape) -> u64 {
+    /*!
+     * The player's score for a single round: the value of the shape played
+     * plus the value of the round outcome.
+     */
+
+    let outcome = determine_round_winner((opponent_shape, player_shape));
+
+    return get_single_shape_value(player_shape) + get_outcome_value(&outcome);
+}
+
 fn get_single_shape_value(shape: &Shape) -> u64 {
     /*!
      * Fetch the value of a single given shape
@@ -165,16 +622,33 @@ fn get_single_shape_value(shape: &Shape) -> u64 {
     return value;
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Debug)]
 enum RoundOutcome {
     PlayerWon,
     OpponentWon,
     Tie,
 }
 
-#[derive(Eq, Hash, PartialEq, Debug)]
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy)]
 enum Shape {
     Rock,
     Paper,
     Scissors,
 }
+
+impl Shape {
+    /// Every shape, in the order used for strategies and payoff matrices.
+    const ALL: [Shape; 3] = [Shape::Rock, Shape::Paper, Shape::Scissors];
+
+    fn index(&self) -> usize {
+        /*!
+         * The position of the shape in `Shape::ALL`.
+         */
+
+        return match self {
+            Shape::Rock => 0,
+            Shape::Paper => 1,
+            Shape::Scissors => 2,
+        };
+    }
+}
@@ -0,0 +1,234 @@
+use crate::{determine_round_winner, get_outcome_value, get_single_shape_value};
+use crate::{RoundOutcome, Shape};
+
+/// Number of wins so far, index of the last shape played and the length of
+/// the current run of that shape (minus one).
+type SolverState = (usize, usize, usize);
+
+pub struct SolverConstraints {
+    pub max_wins: Option<usize>,
+    pub max_repeat: Option<usize>,
+}
+
+pub struct Solution {
+    pub player_shapes: Vec<Shape>,
+    pub total_score: u64,
+    pub wins: usize,
+}
+
+pub fn find_optimal_moves(
+    opponent_shapes: &[Shape],
+    constraints: &SolverConstraints,
+) -> Result<Solution, String> {
+    /*!
+     * Find the sequence of player shapes which maximises the total score
+     * against the given sequence of opponent shapes. The sequence can
+     * optionally be limited to at most `max_wins` won rounds, and to no shape
+     * being played more than `max_repeat` times in a row.
+     *
+     * The search is a dynamic programme over the rounds, with the state being
+     * the number of wins so far, the last shape played and the length of the
+     * current run of that shape. Dimensions for which no constraint is given
+     * are collapsed to a single value.
+     */
+
+    if constraints.max_repeat == Some(0) {
+        return Err(String::from(
+            "The maximum number of repeats must be at least 1.",
+        ));
+    }
+
+    let num_rounds = opponent_shapes.len();
+    let num_win_states = constraints
+        .max_wins
+        .map(|max_wins| max_wins.min(num_rounds) + 1)
+        .unwrap_or(1);
+    // a run can never be longer than the guide
+    let num_run_states = constraints
+        .max_repeat
+        .map(|max_repeat| max_repeat.min(num_rounds).max(1))
+        .unwrap_or(1);
+    let num_states = num_win_states
+        .checked_mul(Shape::ALL.len())
+        .and_then(|num_states| num_states.checked_mul(num_run_states))
+        .ok_or(String::from(
+            "The constraints allow too many solver states.",
+        ))?;
+
+    let state_index = |wins: usize, shape_idx: usize, run: usize| -> usize {
+        return (wins * Shape::ALL.len() + shape_idx) * num_run_states + run;
+    };
+
+    // best score reachable in each state after the current round
+    let mut scores: Vec<Option<u64>> = vec![None; num_states];
+    // for each round, the state from which each state was reached
+    let mut predecessors: Vec<Vec<usize>> = Vec::with_capacity(num_rounds);
+
+    for (round_idx, opponent_shape) in opponent_shapes.iter().enumerate() {
+        let mut next_scores: Vec<Option<u64>> = vec![None; num_states];
+        let mut next_predecessors: Vec<usize> = vec![usize::MAX; num_states];
+
+        // the first round has a single, empty, starting state
+        let previous_states: Vec<(Option<SolverState>, u64)> = if round_idx == 0 {
+            vec![(None, 0)]
+        } else {
+            let mut states = Vec::new();
+            for wins in 0..num_win_states {
+                for shape_idx in 0..Shape::ALL.len() {
+                    for run in 0..num_run_states {
+                        if let Some(score) = scores[state_index(wins, shape_idx, run)] {
+                            states.push((Some((wins, shape_idx, run)), score));
+                        }
+                    }
+                }
+            }
+            states
+        };
+
+        for (previous_state, previous_score) in previous_states {
+            for (shape_idx, player_shape) in Shape::ALL.iter().enumerate() {
+                let outcome = determine_round_winner((opponent_shape, player_shape));
+
+                let previous_wins = previous_state.map(|state| state.0).unwrap_or(0);
+                let wins = if outcome == RoundOutcome::PlayerWon && constraints.max_wins.is_some() {
+                    previous_wins + 1
+                } else {
+                    previous_wins
+                };
+                if wins >= num_win_states {
+                    continue;
+                }
+
+                let run = match (previous_state, constraints.max_repeat) {
+                    (Some((_, previous_shape_idx, previous_run)), Some(_))
+                        if previous_shape_idx == shape_idx =>
+                    {
+                        previous_run + 1
+                    }
+                    _ => 0,
+                };
+                if run >= num_run_states {
+                    continue;
+                }
+
+                let score = previous_score
+                    + get_single_shape_value(player_shape)
+                    + get_outcome_value(&outcome);
+
+                let next_idx = state_index(wins, shape_idx, run);
+                if next_scores[next_idx].is_none_or(|best| score > best) {
+                    next_scores[next_idx] = Some(score);
+                    next_predecessors[next_idx] = previous_state
+                        .map(|(w, s, r)| state_index(w, s, r))
+                        .unwrap_or(usize::MAX);
+                }
+            }
+        }
+
+        scores = next_scores;
+        predecessors.push(next_predecessors);
+    }
+
+    if num_rounds == 0 {
+        return Ok(Solution {
+            player_shapes: Vec::new(),
+            total_score: 0,
+            wins: 0,
+        });
+    }
+
+    let (mut state, total_score) = scores
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, score)| score.map(|score| (idx, score)))
+        .max_by_key(|(_idx, score)| *score)
+        .ok_or(String::from(
+            "No move sequence satisfies the given constraints.",
+        ))?;
+
+    let mut player_shapes: Vec<Shape> = Vec::with_capacity(num_rounds);
+    for round_predecessors in predecessors.iter().rev() {
+        let shape_idx = (state / num_run_states) % Shape::ALL.len();
+        player_shapes.push(Shape::ALL[shape_idx]);
+        state = round_predecessors[state];
+    }
+    player_shapes.reverse();
+
+    let wins = opponent_shapes
+        .iter()
+        .zip(player_shapes.iter())
+        .filter(|(opponent_shape, player_shape)| {
+            determine_round_winner((opponent_shape, player_shape)) == RoundOutcome::PlayerWon
+        })
+        .count();
+
+    return Ok(Solution {
+        player_shapes,
+        total_score,
+        wins,
+    });
+}
+
+pub fn find_constraint_violations(
+    opponent_shapes: &[Shape],
+    player_shapes: &[Shape],
+    constraints: &SolverConstraints,
+) -> Vec<String> {
+    /*!
+     * Describe how a sequence of player shapes breaks the constraints, if it
+     * does. A sequence which breaks them can score more than the constrained
+     * optimum.
+     */
+
+    let mut violations: Vec<String> = Vec::new();
+
+    if let Some(max_wins) = constraints.max_wins {
+        let wins = opponent_shapes
+            .iter()
+            .zip(player_shapes.iter())
+            .filter(|(opponent_shape, player_shape)| {
+                determine_round_winner((opponent_shape, player_shape)) == RoundOutcome::PlayerWon
+            })
+            .count();
+        if wins > max_wins {
+            violations.push(format!("it wins {wins} rounds, more than {max_wins}"));
+        }
+    }
+
+    if let Some(max_repeat) = constraints.max_repeat {
+        let longest_run = player_shapes
+            .chunk_by(|shape_a, shape_b| shape_a == shape_b)
+            .map(<[Shape]>::len)
+            .max()
+            .unwrap_or(0);
+        if longest_run > max_repeat {
+            violations.push(format!(
+                "it plays a shape {longest_run} times in a row, more than {max_repeat}"
+            ));
+        }
+    }
+
+    return violations;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guide_breaking_the_constraints_can_beat_the_optimum() {
+        // the guide `A,Z` wins with paper for 8 points, which --max-wins 0
+        // rules out, leaving a draw with rock for 4 points as the optimum
+        let constraints = SolverConstraints {
+            max_wins: Some(0),
+            max_repeat: None,
+        };
+
+        let solution = find_optimal_moves(&[Shape::Rock], &constraints).unwrap();
+        assert_eq!(solution.total_score, 4);
+        assert_eq!(solution.wins, 0);
+
+        let violations = find_constraint_violations(&[Shape::Rock], &[Shape::Paper], &constraints);
+        assert_eq!(violations, vec!["it wins 1 rounds, more than 0"]);
+    }
+}
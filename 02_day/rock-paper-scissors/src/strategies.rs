@@ -0,0 +1,230 @@
+use crate::{choose_player_shape, RoundOutcome, Shape};
+
+pub trait PlayerStrategy {
+    /// Name under which the strategy is reported.
+    fn name(&self) -> String;
+
+    /// Pick the shape to play in the next round.
+    fn choose_shape(&mut self) -> Shape;
+
+    /// Called after each round with the shapes both sides played, so that
+    /// adaptive strategies can learn from the opponent.
+    fn observe(&mut self, _own_shape: Shape, _opponent_shape: Shape) {}
+}
+
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> SeededRng {
+        return SeededRng { state: seed };
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        /*!
+         * SplitMix64 step. Good enough for simulations, and keeps runs with
+         * the same seed reproducible without pulling in a dependency.
+         */
+
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+
+        return z ^ (z >> 31);
+    }
+
+    pub fn next_shape(&mut self) -> Shape {
+        return Shape::ALL[(self.next_u64() % Shape::ALL.len() as u64) as usize];
+    }
+}
+
+fn get_counter_shape(shape: &Shape) -> Shape {
+    /*!
+     * The shape which beats the given one.
+     */
+
+    return choose_player_shape(shape, &RoundOutcome::PlayerWon);
+}
+
+pub struct FixedSequence {
+    name: String,
+    shapes: Vec<Shape>,
+    round: usize,
+}
+
+impl FixedSequence {
+    pub fn new(name: &str, shapes: Vec<Shape>) -> FixedSequence {
+        /*!
+         * A strategy which plays the given shapes in order, starting over
+         * once the sequence is exhausted.
+         */
+
+        if shapes.is_empty() {
+            panic!("A fixed sequence strategy needs at least one shape.");
+        }
+
+        return FixedSequence {
+            name: name.to_string(),
+            shapes,
+            round: 0,
+        };
+    }
+}
+
+impl PlayerStrategy for FixedSequence {
+    fn name(&self) -> String {
+        return self.name.clone();
+    }
+
+    fn choose_shape(&mut self) -> Shape {
+        let shape = self.shapes[self.round % self.shapes.len()];
+        self.round += 1;
+
+        return shape;
+    }
+}
+
+pub struct RandomStrategy {
+    rng: SeededRng,
+}
+
+impl RandomStrategy {
+    pub fn new(seed: u64) -> RandomStrategy {
+        return RandomStrategy {
+            rng: SeededRng::new(seed),
+        };
+    }
+}
+
+impl PlayerStrategy for RandomStrategy {
+    fn name(&self) -> String {
+        return String::from("random");
+    }
+
+    fn choose_shape(&mut self) -> Shape {
+        return self.rng.next_shape();
+    }
+}
+
+pub struct FrequencyCounter {
+    opponent_counts: [usize; 3],
+    rng: SeededRng,
+}
+
+impl FrequencyCounter {
+    pub fn new(seed: u64) -> FrequencyCounter {
+        /*!
+         * A strategy which plays the counter to the shape the opponent has
+         * played most often so far. Ties are broken at random.
+         */
+
+        return FrequencyCounter {
+            opponent_counts: [0; 3],
+            rng: SeededRng::new(seed),
+        };
+    }
+}
+
+impl PlayerStrategy for FrequencyCounter {
+    fn name(&self) -> String {
+        return String::from("frequency");
+    }
+
+    fn choose_shape(&mut self) -> Shape {
+        let predicted_shape = pick_most_frequent(&self.opponent_counts, &mut self.rng);
+
+        return get_counter_shape(&predicted_shape);
+    }
+
+    fn observe(&mut self, _own_shape: Shape, opponent_shape: Shape) {
+        self.opponent_counts[opponent_shape.index()] += 1;
+    }
+}
+
+pub struct MarkovPredictor {
+    transition_counts: [[usize; 3]; 3],
+    last_opponent_shape: Option<Shape>,
+    rng: SeededRng,
+}
+
+impl MarkovPredictor {
+    pub fn new(seed: u64) -> MarkovPredictor {
+        /*!
+         * A strategy which counts which shape the opponent plays after each
+         * of its shapes, predicts its next shape from the last one, and plays
+         * the counter to the prediction.
+         */
+
+        return MarkovPredictor {
+            transition_counts: [[0; 3]; 3],
+            last_opponent_shape: None,
+            rng: SeededRng::new(seed),
+        };
+    }
+}
+
+impl PlayerStrategy for MarkovPredictor {
+    fn name(&self) -> String {
+        return String::from("markov");
+    }
+
+    fn choose_shape(&mut self) -> Shape {
+        let predicted_shape = match self.last_opponent_shape {
+            Some(last_shape) => {
+                pick_most_frequent(&self.transition_counts[last_shape.index()], &mut self.rng)
+            }
+            None => self.rng.next_shape(),
+        };
+
+        return get_counter_shape(&predicted_shape);
+    }
+
+    fn observe(&mut self, _own_shape: Shape, opponent_shape: Shape) {
+        if let Some(last_shape) = self.last_opponent_shape {
+            self.transition_counts[last_shape.index()][opponent_shape.index()] += 1;
+        }
+
+        self.last_opponent_shape = Some(opponent_shape);
+    }
+}
+
+fn pick_most_frequent(counts: &[usize; 3], rng: &mut SeededRng) -> Shape {
+    /*!
+     * Return the shape with the highest count, choosing at random between
+     * the shapes sharing the highest count.
+     */
+
+    let max_count = counts.iter().max().unwrap();
+    let candidates: Vec<Shape> = Shape::ALL
+        .iter()
+        .zip(counts.iter())
+        .filter(|(_shape, count)| *count == max_count)
+        .map(|(shape, _count)| *shape)
+        .collect();
+
+    return candidates[(rng.next_u64() % candidates.len() as u64) as usize];
+}
+
+pub fn generate_sticky_sequence(length: usize, seed: u64) -> Vec<Shape> {
+    /*!
+     * Generate an opponent sequence which repeats its previous shape half of
+     * the time, and otherwise plays a random shape. Unlike a uniformly random
+     * sequence, this one has a pattern which adaptive strategies can exploit.
+     */
+
+    let mut rng = SeededRng::new(seed);
+    let mut shapes: Vec<Shape> = Vec::with_capacity(length);
+
+    for _ in 0..length {
+        let shape = match shapes.last() {
+            Some(last_shape) if rng.next_u64().is_multiple_of(2) => *last_shape,
+            _ => rng.next_shape(),
+        };
+        shapes.push(shape);
+    }
+
+    return shapes;
+}
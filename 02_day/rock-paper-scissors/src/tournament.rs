@@ -0,0 +1,109 @@
+use crate::strategies::PlayerStrategy;
+use crate::{determine_round_winner, get_round_score, RoundOutcome};
+
+/// Builds a fresh strategy instance from a seed, so that every match starts
+/// from a clean, reproducible state.
+pub type StrategyFactory = Box<dyn Fn(u64) -> Box<dyn PlayerStrategy>>;
+
+#[derive(Default)]
+pub struct TournamentStanding {
+    pub name: String,
+    pub rounds_won: usize,
+    pub rounds_tied: usize,
+    pub rounds_lost: usize,
+    pub matches_won: usize,
+    pub total_score: u64,
+}
+
+impl TournamentStanding {
+    pub fn rounds_played(&self) -> usize {
+        return self.rounds_won + self.rounds_tied + self.rounds_lost;
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        return self.rounds_won as f64 / self.rounds_played().max(1) as f64;
+    }
+
+    pub fn average_score(&self) -> f64 {
+        return self.total_score as f64 / self.rounds_played().max(1) as f64;
+    }
+}
+
+pub fn run_round_robin(
+    participants: &[StrategyFactory],
+    rounds_per_match: usize,
+    seed: u64,
+) -> Vec<TournamentStanding> {
+    /*!
+     * Let every participant play a match of `rounds_per_match` rounds
+     * against every other participant, scoring each side with the same
+     * rules as the strategy guide. Each match gets its own seeds derived from
+     * `seed` and the participants' positions, so results are reproducible.
+     */
+
+    let mut standings: Vec<TournamentStanding> = participants
+        .iter()
+        .enumerate()
+        .map(|(idx, build)| TournamentStanding {
+            name: build(derive_seed(seed, idx, idx)).name(),
+            ..Default::default()
+        })
+        .collect();
+
+    for first_idx in 0..participants.len() {
+        for second_idx in (first_idx + 1)..participants.len() {
+            let mut first = participants[first_idx](derive_seed(seed, first_idx, second_idx));
+            let mut second = participants[second_idx](derive_seed(seed, second_idx, first_idx));
+
+            let mut first_match_score = 0;
+            let mut second_match_score = 0;
+
+            for _ in 0..rounds_per_match {
+                let first_shape = first.choose_shape();
+                let second_shape = second.choose_shape();
+
+                let first_score = get_round_score(&second_shape, &first_shape);
+                let second_score = get_round_score(&first_shape, &second_shape);
+                first_match_score += first_score;
+                second_match_score += second_score;
+
+                match determine_round_winner((&second_shape, &first_shape)) {
+                    RoundOutcome::PlayerWon => {
+                        standings[first_idx].rounds_won += 1;
+                        standings[second_idx].rounds_lost += 1;
+                    }
+                    RoundOutcome::OpponentWon => {
+                        standings[first_idx].rounds_lost += 1;
+                        standings[second_idx].rounds_won += 1;
+                    }
+                    RoundOutcome::Tie => {
+                        standings[first_idx].rounds_tied += 1;
+                        standings[second_idx].rounds_tied += 1;
+                    }
+                }
+
+                first.observe(first_shape, second_shape);
+                second.observe(second_shape, first_shape);
+            }
+
+            standings[first_idx].total_score += first_match_score;
+            standings[second_idx].total_score += second_match_score;
+
+            if first_match_score > second_match_score {
+                standings[first_idx].matches_won += 1;
+            } else if second_match_score > first_match_score {
+                standings[second_idx].matches_won += 1;
+            }
+        }
+    }
+
+    return standings;
+}
+
+fn derive_seed(seed: u64, own_idx: usize, opponent_idx: usize) -> u64 {
+    return seed
+        .wrapping_mul(31)
+        .wrapping_add(own_idx as u64)
+        .wrapping_mul(31)
+        .wrapping_add(opponent_idx as u64);
+}
@@ -0,0 +1,115 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use crate::item_set::{intersect_item_sets, ItemSet};
+
+pub fn run_benchmark(num_rucksacks: usize, seed: u64) {
+    /*!
+     * Compares compartment and group intersections done with HashSets and
+     * with ItemSets on generated rucksacks, and prints the timings.
+     */
+
+    let rucksacks = generate_rucksacks(num_rucksacks, seed);
+    println!("Generated {num_rucksacks} rucksacks (seed {seed}).");
+
+    let (hash_set_elapsed, hash_set_checksum) = time_it(|| count_with_hash_sets(&rucksacks));
+    let (item_set_elapsed, item_set_checksum) = time_it(|| count_with_item_sets(&rucksacks));
+
+    if hash_set_checksum != item_set_checksum {
+        panic!("HashSet and ItemSet intersections disagree ({hash_set_checksum} vs {item_set_checksum}).");
+    }
+
+    println!("HashSet: {hash_set_elapsed:?}");
+    println!("ItemSet: {item_set_elapsed:?}");
+    println!(
+        "ItemSet speed-up: {:.1}x",
+        hash_set_elapsed.as_secs_f64() / item_set_elapsed.as_secs_f64().max(f64::EPSILON)
+    );
+}
+
+fn time_it<F>(run: F) -> (Duration, usize)
+where
+    F: Fn() -> usize,
+{
+    let start = Instant::now();
+    let checksum = run();
+
+    return (start.elapsed(), checksum);
+}
+
+fn count_with_hash_sets(rucksacks: &[String]) -> usize {
+    /*!
+     * Counts the items shared by compartments and by groups of three, using
+     * the HashSet approach the crate used originally.
+     */
+
+    let mut shared_count = 0;
+
+    for inventory in rucksacks {
+        let items: Vec<char> = inventory.chars().collect();
+        let (compartment_1, compartment_2) = items.split_at(items.len() / 2);
+        let compartment_1: HashSet<char> = compartment_1.iter().copied().collect();
+        let compartment_2: HashSet<char> = compartment_2.iter().copied().collect();
+        shared_count += compartment_1.intersection(&compartment_2).count();
+    }
+
+    for group in rucksacks.chunks_exact(3) {
+        let elf_1: HashSet<char> = group[0].chars().collect();
+        let elf_2: HashSet<char> = group[1].chars().collect();
+        let elf_3: HashSet<char> = group[2].chars().collect();
+        let intersection_1_2: HashSet<char> = elf_1.intersection(&elf_2).copied().collect();
+        shared_count += intersection_1_2.intersection(&elf_3).count();
+    }
+
+    return shared_count;
+}
+
+fn count_with_item_sets(rucksacks: &[String]) -> usize {
+    let mut shared_count = 0;
+
+    for inventory in rucksacks {
+        let items: Vec<char> = inventory.chars().collect();
+        let (compartment_1, compartment_2) = items.split_at(items.len() / 2);
+        let compartment_1: ItemSet = compartment_1.iter().copied().collect();
+        let compartment_2: ItemSet = compartment_2.iter().copied().collect();
+        shared_count += compartment_1.intersection(&compartment_2).len();
+    }
+
+    for group in rucksacks.chunks_exact(3) {
+        let elf_sets: Vec<ItemSet> = group
+            .iter()
+            .map(|inventory| inventory.chars().collect())
+            .collect();
+        shared_count += intersect_item_sets(&elf_sets).len();
+    }
+
+    return shared_count;
+}
+
+fn generate_rucksacks(num_rucksacks: usize, seed: u64) -> Vec<String> {
+    /*!
+     * Generates rucksacks of random letters, with an even number of items
+     * between 2 and 64.
+     */
+
+    let letters: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+    let mut rng_state = seed;
+
+    let mut next_random = || {
+        // SplitMix64
+        rng_state = rng_state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = rng_state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        return (z ^ (z >> 31)) as usize;
+    };
+
+    return (0..num_rucksacks)
+        .map(|_| {
+            let num_items = 2 * (1 + next_random() % 32);
+            (0..num_items)
+                .map(|_| letters[next_random() % letters.len()])
+                .collect()
+        })
+        .collect();
+}
@@ -0,0 +1,47 @@
+use std::fmt;
+
+/// Describes a rucksack or group of rucksacks for which no unique shared
+/// item could be found.
+pub struct Diagnostic {
+    /// What was being looked for, e.g. "Group 4" or "Rucksack 12".
+    pub subject: String,
+    pub reason: String,
+    /// The input lines involved, as line numbers with their contents.
+    pub lines: Vec<(usize, String)>,
+    /// The items the input lines have in common.
+    pub candidates: Vec<char>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(formatter, "{}: {}", self.subject, self.reason)?;
+
+        for (line_number, line) in &self.lines {
+            writeln!(formatter, "    line {line_number}: {line}")?;
+        }
+
+        let candidates: Vec<String> = self
+            .candidates
+            .iter()
+            .map(|item| item.to_string())
+            .collect();
+        if candidates.is_empty() {
+            write!(formatter, "    candidates: none")?;
+        } else {
+            write!(formatter, "    candidates: {}", candidates.join(", "))?;
+        }
+
+        return Ok(());
+    }
+}
+
+pub fn describe_candidates(candidates: &[char]) -> String {
+    /*!
+     * Explains why a set of common items does not identify a single item.
+     */
+
+    return match candidates.len() {
+        0 => String::from("expected a single common item, found none"),
+        num_candidates => format!("expected a single common item, found {num_candidates}"),
+    };
+}
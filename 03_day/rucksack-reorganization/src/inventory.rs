@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+
+use crate::item_set::{intersect_item_sets, ItemSet};
+
+/// The parsed rucksacks of all elves, with the set of item types in each.
+pub struct Inventory {
+    rucksacks: Vec<String>,
+    item_sets: Vec<ItemSet>,
+}
+
+pub struct ItemCount {
+    /// How many of the item are packed in total.
+    pub total: usize,
+    /// How many rucksacks contain the item.
+    pub rucksacks: usize,
+}
+
+impl Inventory {
+    pub fn new(rucksacks: &[String]) -> Inventory {
+        return Inventory {
+            rucksacks: rucksacks.to_vec(),
+            item_sets: rucksacks
+                .iter()
+                .map(|inventory| inventory.chars().collect())
+                .collect(),
+        };
+    }
+
+    pub fn find_rucksacks_containing(&self, item: char) -> Vec<usize> {
+        /*!
+         * Line numbers of the rucksacks containing the given item.
+         */
+
+        return self
+            .item_sets
+            .iter()
+            .enumerate()
+            .filter(|(_idx, item_set)| item_set.contains(item))
+            .map(|(idx, _item_set)| idx + 1)
+            .collect();
+    }
+
+    pub fn count_items(&self) -> BTreeMap<char, ItemCount> {
+        /*!
+         * Counts every item type, both in total and by the number of rucksacks
+         * it appears in.
+         */
+
+        let mut item_counts: BTreeMap<char, ItemCount> = BTreeMap::new();
+
+        for (inventory, item_set) in self.rucksacks.iter().zip(self.item_sets.iter()) {
+            for item in inventory.chars() {
+                item_counts
+                    .entry(item)
+                    .or_insert(ItemCount {
+                        total: 0,
+                        rucksacks: 0,
+                    })
+                    .total += 1;
+            }
+
+            for item in item_set.items() {
+                item_counts.get_mut(&item).unwrap().rucksacks += 1;
+            }
+        }
+
+        return item_counts;
+    }
+
+    pub fn find_unique_items(&self) -> Vec<(char, usize)> {
+        /*!
+         * Items carried by a single elf, with the line number of the rucksack
+         * they are in.
+         */
+
+        return self
+            .count_items()
+            .iter()
+            .filter(|(_item, count)| count.rucksacks == 1)
+            .map(|(item, _count)| (*item, self.find_rucksacks_containing(*item)[0]))
+            .collect();
+    }
+
+    pub fn find_items_shared_by_all(&self) -> Vec<char> {
+        /*!
+         * Items carried by every elf.
+         */
+
+        return intersect_item_sets(&self.item_sets).items();
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::BTreeSet;
+
+/// A set of item types. The letters `a-z` and `A-Z` are stored as bits of a
+/// single u64, indexed by their priority minus one, so that intersecting two
+/// sets is a bitwise AND. Any other item falls back to a regular set, which
+/// stays empty (and unallocated) for the usual inputs.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct ItemSet {
+    letters: u64,
+    other_items: BTreeSet<char>,
+}
+
+impl ItemSet {
+    pub fn new() -> ItemSet {
+        return ItemSet::default();
+    }
+
+    pub fn insert(&mut self, item: char) {
+        match get_letter_bit(item) {
+            Some(bit) => self.letters |= 1 << bit,
+            None => {
+                self.other_items.insert(item);
+            }
+        }
+    }
+
+    pub fn contains(&self, item: char) -> bool {
+        return match get_letter_bit(item) {
+            Some(bit) => self.letters & (1 << bit) != 0,
+            None => self.other_items.contains(&item),
+        };
+    }
+
+    pub fn intersection(&self, other: &ItemSet) -> ItemSet {
+        return ItemSet {
+            letters: self.letters & other.letters,
+            other_items: self
+                .other_items
+                .intersection(&other.other_items)
+                .copied()
+                .collect(),
+        };
+    }
+
+    pub fn len(&self) -> usize {
+        return self.letters.count_ones() as usize + self.other_items.len();
+    }
+
+    pub fn items(&self) -> Vec<char> {
+        /*!
+         * The items in the set, letters first in priority order, followed by
+         * any other items.
+         */
+
+        let mut items: Vec<char> = ('a'..='z')
+            .chain('A'..='Z')
+            .filter(|item| self.contains(*item))
+            .collect();
+        items.extend(self.other_items.iter());
+
+        return items;
+    }
+}
+
+impl FromIterator<char> for ItemSet {
+    fn from_iter<I: IntoIterator<Item = char>>(items: I) -> ItemSet {
+        let mut item_set = ItemSet::new();
+        for item in items {
+            item_set.insert(item);
+        }
+
+        return item_set;
+    }
+}
+
+fn get_letter_bit(item: char) -> Option<u32> {
+    return match item {
+        'a'..='z' => Some(item as u32 - 'a' as u32),
+        'A'..='Z' => Some(item as u32 - 'A' as u32 + 26),
+        _ => None,
+    };
+}
+
+pub fn intersect_item_sets(item_sets: &[ItemSet]) -> ItemSet {
+    /*!
+     * Finds the items contained in every one of the given sets.
+     */
+
+    let Some((first_set, other_sets)) = item_sets.split_first() else {
+        return ItemSet::new();
+    };
+
+    return other_sets
+        .iter()
+        .fold(first_set.clone(), |common_items, item_set| {
+            common_items.intersection(item_set)
+        });
+}
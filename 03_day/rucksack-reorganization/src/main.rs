@@ -1,61 +1,428 @@
-use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader, Lines};
-use std::path::Path;
+#![allow(clippy::needless_return)]
+
+mod benchmark;
+mod diagnostics;
+mod inventory;
+mod item_set;
+mod planner;
+mod priority;
 
-const ALPHABET_LOWERCASE: [char; 26] = [
-    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's',
-    't', 'u', 'v', 'w', 'x', 'y', 'z',
-];
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Lines};
+use std::path::{Path, PathBuf};
 
-const ALPHABET_UPPERCASE: [char; 26] = [
-    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S',
-    'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
-];
+use diagnostics::Diagnostic;
+use inventory::Inventory;
+use item_set::{intersect_item_sets, ItemSet};
+use priority::{AlphabetPriority, MappedPriority, PriorityScheme};
 
 fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if args.first().map(String::as_str) == Some("bench") {
+        let num_rucksacks = get_option_value(&args, "--rucksacks")
+            .map(|value| value.parse::<usize>().unwrap())
+            .unwrap_or(300_000);
+        let seed = get_option_value(&args, "--seed")
+            .map(|value| value.parse::<u64>().unwrap())
+            .unwrap_or(2022);
+        benchmark::run_benchmark(num_rucksacks, seed);
+        return;
+    }
+
+    let group_size = match get_option_value(&args, "--group-size") {
+        Some(value) => match value.parse::<usize>() {
+            Ok(group_size) if group_size > 0 => group_size,
+            _ => {
+                eprintln!("Invalid group size: {value}.");
+                std::process::exit(1);
+            }
+        },
+        None => 3,
+    };
+    let strict = args.iter().any(|arg| arg == "--strict");
+
+    let priority_scheme: Box<dyn PriorityScheme> = match get_option_value(&args, "--priorities") {
+        Some(mapping_file) => match MappedPriority::load(mapping_file) {
+            Ok(mapping) => Box::new(mapping),
+            Err(message) => {
+                eprintln!("{message}");
+                std::process::exit(1);
+            }
+        },
+        None => Box::new(AlphabetPriority),
+    };
+
     let current_file = Path::new(file!());
-    let data_filepath = current_file
-        .parent()
-        .unwrap()
-        .parent()
-        .unwrap()
-        .join("data")
-        .join("data.txt");
-
-    let rucksacks = read_inventory(data_filepath);
-
-    let mut group_badges: Vec<char> = Vec::new();
-    let mut elf_group: Vec<HashSet<char>> = Vec::new();
-    for (idx, inventory) in rucksacks.enumerate() {
-        if let Ok(inventory) = inventory {
-            let elf_unique_items: HashSet<char> = inventory.chars().collect();
-            elf_group.push(elf_unique_items);
-        }
-
-        if (idx + 1) % 3 == 0 {
-            let intersection_1_2: HashSet<char> = elf_group[0]
-                .intersection(&elf_group[1])
-                .map(|x| *x)
-                .collect();
+    let data_filepath = match get_option_value(&args, "--inventory") {
+        Some(inventory_file) => PathBuf::from(inventory_file),
+        None => current_file
+            .parent()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .join("data")
+            .join("data.txt"),
+    };
+
+    let rucksacks: Vec<String> = read_inventory(data_filepath)
+        .map(|line| line.unwrap())
+        .collect();
 
-            let intersection_all: Vec<char> = intersection_1_2
-                .intersection(&elf_group[2])
-                .map(|x| *x)
+    if args.first().map(String::as_str) == Some("query") {
+        run_query(&Inventory::new(&rucksacks), &args[1..]);
+        return;
+    }
+
+    if args.first().map(String::as_str) == Some("plan") {
+        run_planner(
+            &rucksacks,
+            priority_scheme.as_ref(),
+            get_option_value(&args, "--output"),
+        );
+        return;
+    }
+
+    let keep_going = args.iter().any(|arg| arg == "--keep-going");
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    let (wrongly_sorted_items, compartment_diagnostics) =
+        find_wrongly_sorted_items(&rucksacks, keep_going);
+    check_diagnostics(&compartment_diagnostics, keep_going);
+    diagnostics.extend(compartment_diagnostics);
+
+    let total_priority = get_total_item_priority(wrongly_sorted_items, priority_scheme.as_ref())
+        .unwrap_or_else(|message| {
+            eprintln!("{message}");
+            std::process::exit(1);
+        });
+    println!("Total priority of items in both compartments is {total_priority}.");
+
+    let trailing_elves = rucksacks.len() % group_size;
+    if trailing_elves != 0 {
+        let message = format!(
+            "The last {trailing_elves} rucksacks do not make up a full group of {group_size} elves."
+        );
+        if strict {
+            eprintln!("Error: {message}");
+            std::process::exit(1);
+        }
+        eprintln!("Warning: {message} They are left out of the badge analysis.");
+    }
+
+    let (elf_groups, group_diagnostics) = find_group_badges(&rucksacks, group_size, keep_going);
+    check_diagnostics(&group_diagnostics, keep_going);
+    diagnostics.extend(group_diagnostics);
+
+    if args.iter().any(|arg| arg == "--groups") {
+        print_group_report(&elf_groups);
+    }
+
+    let group_badges: Vec<char> = elf_groups.iter().map(|group| group.badge).collect();
+    let total_priority = get_total_item_priority(group_badges, priority_scheme.as_ref())
+        .unwrap_or_else(|message| {
+            eprintln!("{message}");
+            std::process::exit(1);
+        });
+    println!("Total badge priority is {total_priority}.");
+
+    if !diagnostics.is_empty() {
+        eprintln!();
+        eprintln!(
+            "{} rucksacks or groups were skipped and are not included in the totals:",
+            diagnostics.len()
+        );
+        for diagnostic in &diagnostics {
+            eprintln!("{diagnostic}");
+        }
+        std::process::exit(1);
+    }
+}
+
+fn run_query(inventory: &Inventory, args: &[String]) {
+    /*!
+     * Answers one of the inventory queries: `contains <item>`, `counts`,
+     * `unique` or `shared`.
+     */
+
+    match (args.first().map(String::as_str), args.get(1)) {
+        (Some("contains"), Some(item)) => {
+            let mut item_chars = item.chars();
+            let item = match (item_chars.next(), item_chars.next()) {
+                (Some(item), None) => item,
+                _ => {
+                    eprintln!("Expected a single item, got {item:?}.");
+                    std::process::exit(1);
+                }
+            };
+
+            let line_numbers = inventory.find_rucksacks_containing(item);
+            println!("{} rucksacks contain {item}:", line_numbers.len());
+            for line_number in line_numbers {
+                println!("    line {line_number}");
+            }
+        }
+        (Some("counts"), _) => {
+            println!("item  total  rucksacks");
+            for (item, count) in inventory.count_items() {
+                println!("{item:>4}  {:>5}  {:>9}", count.total, count.rucksacks);
+            }
+        }
+        (Some("unique"), _) => {
+            let unique_items = inventory.find_unique_items();
+            println!("{} items are carried by a single elf:", unique_items.len());
+            for (item, line_number) in unique_items {
+                println!("    {item}: line {line_number}");
+            }
+        }
+        (Some("shared"), _) => {
+            let shared_items: Vec<String> = inventory
+                .find_items_shared_by_all()
+                .iter()
+                .map(|item| item.to_string())
                 .collect();
+            println!(
+                "{} items are carried by every elf: {}",
+                shared_items.len(),
+                shared_items.join(", ")
+            );
+        }
+        _ => {
+            eprintln!("Usage: query contains <item> | query counts | query unique | query shared");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_planner(
+    rucksacks: &[String],
+    priority_scheme: &dyn PriorityScheme,
+    output_file: Option<&str>,
+) {
+    /*!
+     * Plans the moves fixing each rucksack's compartments, prints them, and
+     * writes out the corrected inventory, either to `output_file` or after
+     * the plan. Rucksacks which cannot be fixed are reported and left as they
+     * are.
+     *
+     * Only moves between the two compartments of a rucksack are planned;
+     * items are never moved between the rucksacks of a group.
+     */
+
+    println!("Planning moves between compartments only, items stay in their rucksack.");
+
+    let mut corrected_inventory: Vec<String> = Vec::new();
+    let mut moved_items: Vec<char> = Vec::new();
+    let mut num_unfixable = 0;
 
-            if intersection_all.len() == 1 {
-                group_badges.push(intersection_all[0]);
-            } else {
-                panic!("Unable to find unique group identifier!")
+    for (idx, inventory) in rucksacks.iter().enumerate() {
+        match planner::plan_rucksack(inventory, priority_scheme) {
+            Ok(plan) => {
+                let moves: Vec<String> = plan
+                    .moves
+                    .iter()
+                    .map(|item_move| {
+                        format!(
+                            "{}x {} from compartment {} to {}",
+                            item_move.count,
+                            item_move.item,
+                            item_move.from_compartment,
+                            3 - item_move.from_compartment
+                        )
+                    })
+                    .collect();
+                if !moves.is_empty() {
+                    println!("Rucksack {}: {}", idx + 1, moves.join(", "));
+                }
+
+                moved_items.extend(plan.get_moved_items());
+                corrected_inventory.push(plan.corrected_inventory);
+            }
+            Err(reason) => {
+                println!("Rucksack {}: cannot be fixed, {reason}", idx + 1);
+                num_unfixable += 1;
+                corrected_inventory.push(inventory.clone());
             }
+        }
+    }
 
-            elf_group = Vec::new();
+    println!(
+        "{} items moved, with a total priority of {}; {num_unfixable} rucksacks could not be fixed.",
+        moved_items.len(),
+        get_total_item_priority(moved_items, priority_scheme).unwrap()
+    );
+
+    match output_file {
+        Some(output_file) => {
+            fs::write(output_file, corrected_inventory.join("\n") + "\n").unwrap();
+            println!("Corrected inventory written to {output_file}.");
+        }
+        None => {
+            println!();
+            for inventory in corrected_inventory {
+                println!("{inventory}");
+            }
         }
     }
+}
 
-    let total_priority = get_total_item_priority(group_badges);
-    println!("Total badge priority is {total_priority}.");
+fn check_diagnostics(diagnostics: &[Diagnostic], keep_going: bool) {
+    /*!
+     * Unless running with `--keep-going`, stop at the first rucksack or group
+     * without a unique shared item.
+     */
+
+    if keep_going {
+        return;
+    }
+
+    if let Some(diagnostic) = diagnostics.first() {
+        eprintln!("{diagnostic}");
+        eprintln!("Run with --keep-going to report all such rucksacks and groups.");
+        std::process::exit(1);
+    }
+}
+
+fn get_option_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    /*!
+     * Fetch the value following the given flag in the command line arguments.
+     */
+
+    let flag_idx = args.iter().position(|arg| arg == flag)?;
+
+    return args.get(flag_idx + 1).map(String::as_str);
+}
+
+fn find_wrongly_sorted_items(
+    rucksacks: &[String],
+    keep_going: bool,
+) -> (Vec<char>, Vec<Diagnostic>) {
+    /*!
+     * Splits each rucksack into its two equally sized compartments and finds
+     * the item type which was put into both of them. Rucksacks for which this
+     * fails are described by diagnostics; unless `keep_going` is set, the
+     * search stops at the first one.
+     */
+
+    let mut wrongly_sorted_items: Vec<char> = Vec::new();
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    for (idx, inventory) in rucksacks.iter().enumerate() {
+        if !diagnostics.is_empty() && !keep_going {
+            break;
+        }
+
+        let items: Vec<char> = inventory.chars().collect();
+
+        if !items.len().is_multiple_of(2) {
+            diagnostics.push(Diagnostic {
+                subject: format!("Rucksack {}", idx + 1),
+                reason: format!(
+                    "odd number of items ({}), so it cannot be split into compartments",
+                    items.len()
+                ),
+                lines: vec![(idx + 1, inventory.clone())],
+                candidates: Vec::new(),
+            });
+            continue;
+        }
+
+        let (compartment_1, compartment_2) = items.split_at(items.len() / 2);
+        let compartment_1: ItemSet = compartment_1.iter().copied().collect();
+        let compartment_2: ItemSet = compartment_2.iter().copied().collect();
+
+        let shared_items: Vec<char> = compartment_1.intersection(&compartment_2).items();
+
+        if shared_items.len() == 1 {
+            wrongly_sorted_items.push(shared_items[0]);
+        } else {
+            diagnostics.push(Diagnostic {
+                subject: format!("Rucksack {}", idx + 1),
+                reason: format!(
+                    "{} in the two compartments",
+                    diagnostics::describe_candidates(&shared_items)
+                ),
+                lines: vec![(idx + 1, inventory.clone())],
+                candidates: shared_items,
+            });
+        }
+    }
+
+    return (wrongly_sorted_items, diagnostics);
+}
+
+fn find_group_badges(
+    rucksacks: &[String],
+    group_size: usize,
+    keep_going: bool,
+) -> (Vec<ElfGroup>, Vec<Diagnostic>) {
+    /*!
+     * Splits the rucksacks into consecutive groups of `group_size` elves and
+     * finds the badge of each group, i.e. the only item type carried by all
+     * the elves in the group. Rucksacks which do not make up a full group at
+     * the end are left out. Groups without a unique badge are described by
+     * diagnostics; unless `keep_going` is set, the search stops at the first
+     * one.
+     */
+
+    let mut elf_groups: Vec<ElfGroup> = Vec::new();
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    for (group_idx, members) in rucksacks.chunks_exact(group_size).enumerate() {
+        if !diagnostics.is_empty() && !keep_going {
+            break;
+        }
+
+        let first_line = group_idx * group_size + 1;
+
+        let elf_unique_items: Vec<ItemSet> = members
+            .iter()
+            .map(|inventory| inventory.chars().collect())
+            .collect();
+
+        let intersection_all: Vec<char> = intersect_item_sets(&elf_unique_items).items();
+
+        if intersection_all.len() == 1 {
+            elf_groups.push(ElfGroup {
+                number: group_idx + 1,
+                first_line,
+                members: members.to_vec(),
+                badge: intersection_all[0],
+            });
+        } else {
+            diagnostics.push(Diagnostic {
+                subject: format!("Group {}", group_idx + 1),
+                reason: format!(
+                    "unable to find unique group identifier, {}",
+                    diagnostics::describe_candidates(&intersection_all)
+                ),
+                lines: (first_line..).zip(members.iter().cloned()).collect(),
+                candidates: intersection_all,
+            });
+        }
+    }
+
+    return (elf_groups, diagnostics);
+}
+
+fn print_group_report(elf_groups: &[ElfGroup]) {
+    /*!
+     * Prints the rucksacks making up each group, together with its badge.
+     */
+
+    for group in elf_groups {
+        println!(
+            "Group {} (lines {}-{}), badge {}:",
+            group.number,
+            group.first_line,
+            group.first_line + group.members.len() - 1,
+            group.badge
+        );
+        for member in &group.members {
+            println!("    {member}");
+        }
+    }
 }
 
 fn read_inventory<P>(file: P) -> Lines<BufReader<File>>
@@ -70,26 +437,31 @@ where
     return BufReader::new(file_conn).lines();
 }
 
-fn get_total_item_priority(wrongly_sorted_items: Vec<char>) -> usize {
+fn get_total_item_priority(
+    wrongly_sorted_items: Vec<char>,
+    priority_scheme: &dyn PriorityScheme,
+) -> Result<usize, String> {
     /*!
      * Finds the sum of priorities of all wrongly sorted items found in the
-     * data.
+     * data. Fails on the first item the priority scheme has no priority for.
      */
 
-    let total_priority: usize = wrongly_sorted_items
-        .iter()
-        .map(|item| {
-            if let Ok(idx) = ALPHABET_LOWERCASE.binary_search(&item) {
-                return idx + 1;
-            }
+    let mut total_priority = 0;
 
-            if let Ok(idx) = ALPHABET_UPPERCASE.binary_search(&item) {
-                return ALPHABET_UPPERCASE.len() + 1 + idx;
-            }
+    for item in wrongly_sorted_items {
+        match priority_scheme.get_priority(item) {
+            Some(priority) => total_priority += priority,
+            None => return Err(format!("No priority is defined for item {item:?}.")),
+        }
+    }
 
-            return 0;
-        })
-        .sum();
+    return Ok(total_priority);
+}
 
-    return total_priority;
+struct ElfGroup {
+    number: usize,
+    /// Line of the input on which the group's first rucksack is listed.
+    first_line: usize,
+    members: Vec<String>,
+    badge: char,
 }
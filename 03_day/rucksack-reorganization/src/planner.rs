@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+use crate::priority::PriorityScheme;
+
+pub struct ItemMove {
+    pub item: char,
+    pub count: usize,
+    /// Compartment the items are moved out of, 1 or 2.
+    pub from_compartment: usize,
+}
+
+pub struct RucksackPlan {
+    pub moves: Vec<ItemMove>,
+    pub corrected_inventory: String,
+}
+
+impl RucksackPlan {
+    pub fn get_moved_items(&self) -> Vec<char> {
+        /*!
+         * Every moved item, repeated as many times as it was moved.
+         */
+
+        return self
+            .moves
+            .iter()
+            .flat_map(|item_move| std::iter::repeat_n(item_move.item, item_move.count))
+            .collect();
+    }
+}
+
+pub fn plan_rucksack(
+    inventory: &str,
+    priority_scheme: &dyn PriorityScheme,
+) -> Result<RucksackPlan, String> {
+    /*!
+     * Finds the fewest single-item moves between the two compartments of a
+     * rucksack after which no item type is in both compartments, and both
+     * compartments still hold the same number of items. When several plans
+     * need the same number of moves, the one moving the lowest total priority
+     * is chosen, so that high priority items stay where they are.
+     *
+     * Every item type has to end up wholly in one compartment, so this is a
+     * knapsack over the item types: pick the types which go into the first
+     * compartment so that it is exactly half full, at the lowest cost.
+     */
+
+    let items: Vec<char> = inventory.chars().collect();
+    if !items.len().is_multiple_of(2) {
+        return Err(format!(
+            "odd number of items ({}), so it cannot be split into compartments",
+            items.len()
+        ));
+    }
+    let compartment_size = items.len() / 2;
+    let (compartment_1, compartment_2) = items.split_at(compartment_size);
+
+    // item type -> (count in compartment 1, count in compartment 2)
+    let mut item_counts: BTreeMap<char, (usize, usize)> = BTreeMap::new();
+    for item in compartment_1 {
+        item_counts.entry(*item).or_insert((0, 0)).0 += 1;
+    }
+    for item in compartment_2 {
+        item_counts.entry(*item).or_insert((0, 0)).1 += 1;
+    }
+    let item_types: Vec<(char, usize, usize)> = item_counts
+        .into_iter()
+        .map(|(item, (count_1, count_2))| (item, count_1, count_2))
+        .collect();
+
+    // best[i][size]: cheapest (moves, moved priority) for placing the first i
+    // item types with `size` items in compartment 1
+    let mut best: Vec<Vec<Option<(usize, usize)>>> =
+        vec![vec![None; compartment_size + 1]; item_types.len() + 1];
+    best[0][0] = Some((0, 0));
+
+    for (type_idx, (item, count_1, count_2)) in item_types.iter().enumerate() {
+        let priority = priority_scheme
+            .get_priority(*item)
+            .ok_or(format!("no priority is defined for item {item:?}"))?;
+
+        for size in 0..=compartment_size {
+            let Some((moves, moved_priority)) = best[type_idx][size] else {
+                continue;
+            };
+
+            // keep the item type in compartment 1, moving in the copies from 2
+            let size_1 = size + count_1 + count_2;
+            let cost_1 = (moves + count_2, moved_priority + count_2 * priority);
+            // or keep it in compartment 2, moving out the copies in 1
+            let cost_2 = (moves + count_1, moved_priority + count_1 * priority);
+
+            for (next_size, cost) in [(size_1, cost_1), (size, cost_2)] {
+                if next_size > compartment_size {
+                    continue;
+                }
+                let next_best = &mut best[type_idx + 1][next_size];
+                if next_best.is_none_or(|current| cost < current) {
+                    *next_best = Some(cost);
+                }
+            }
+        }
+    }
+
+    if best[item_types.len()][compartment_size].is_none() {
+        return Err(String::from(
+            "the item types cannot be split into two equally sized compartments",
+        ));
+    }
+
+    // walk back through the table to recover where each item type goes
+    let mut in_compartment_1: Vec<char> = Vec::new();
+    let mut moves: Vec<ItemMove> = Vec::new();
+    let mut size = compartment_size;
+    for (type_idx, (item, count_1, count_2)) in item_types.iter().enumerate().rev() {
+        let cost = best[type_idx + 1][size].unwrap();
+        let priority = priority_scheme.get_priority(*item).unwrap();
+
+        let stays_in_1 = size >= count_1 + count_2
+            && best[type_idx][size - count_1 - count_2].is_some_and(|(moves, moved_priority)| {
+                (moves + count_2, moved_priority + count_2 * priority) == cost
+            });
+
+        if stays_in_1 {
+            in_compartment_1.push(*item);
+            size -= count_1 + count_2;
+            if *count_2 > 0 {
+                moves.push(ItemMove {
+                    item: *item,
+                    count: *count_2,
+                    from_compartment: 2,
+                });
+            }
+        } else if *count_1 > 0 {
+            moves.push(ItemMove {
+                item: *item,
+                count: *count_1,
+                from_compartment: 1,
+            });
+        }
+    }
+
+    // items which stay keep their order, moved items go to the end
+    let mut corrected_1: Vec<char> = Vec::with_capacity(compartment_size);
+    let mut corrected_2: Vec<char> = Vec::with_capacity(compartment_size);
+    for item in compartment_1 {
+        if in_compartment_1.contains(item) {
+            corrected_1.push(*item);
+        }
+    }
+    for item in compartment_2 {
+        if !in_compartment_1.contains(item) {
+            corrected_2.push(*item);
+        }
+    }
+    for item in compartment_2 {
+        if in_compartment_1.contains(item) {
+            corrected_1.push(*item);
+        }
+    }
+    for item in compartment_1 {
+        if !in_compartment_1.contains(item) {
+            corrected_2.push(*item);
+        }
+    }
+
+    // list the most valuable moves first
+    moves.sort_by_key(|item_move| {
+        std::cmp::Reverse(priority_scheme.get_priority(item_move.item).unwrap())
+    });
+
+    return Ok(RucksackPlan {
+        moves,
+        corrected_inventory: corrected_1.into_iter().chain(corrected_2).collect(),
+    });
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const ALPHABET_LOWERCASE: [char; 26] = [
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's',
+    't', 'u', 'v', 'w', 'x', 'y', 'z',
+];
+
+const ALPHABET_UPPERCASE: [char; 26] = [
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S',
+    'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
+];
+
+pub trait PriorityScheme {
+    /// The priority of the given item type, or None if the scheme does not
+    /// define one.
+    fn get_priority(&self, item: char) -> Option<usize>;
+}
+
+/// The puzzle's priorities: a-z are 1 to 26, A-Z are 27 to 52.
+pub struct AlphabetPriority;
+
+impl PriorityScheme for AlphabetPriority {
+    fn get_priority(&self, item: char) -> Option<usize> {
+        if let Ok(idx) = ALPHABET_LOWERCASE.binary_search(&item) {
+            return Some(idx + 1);
+        }
+
+        if let Ok(idx) = ALPHABET_UPPERCASE.binary_search(&item) {
+            return Some(ALPHABET_UPPERCASE.len() + 1 + idx);
+        }
+
+        return None;
+    }
+}
+
+/// Priorities given by an explicit item to priority mapping.
+pub struct MappedPriority {
+    priorities: HashMap<char, usize>,
+}
+
+impl MappedPriority {
+    pub fn load<P>(file: P) -> Result<MappedPriority, String>
+    where
+        P: AsRef<Path>,
+    {
+        /*!
+         * Loads a mapping file with one `<item> <priority>` pair per line.
+         * Empty lines and lines starting with `#` are ignored. The mapping
+         * starts out as the alphabet priorities, which the file can extend or
+         * override, so it only needs to list the extra item types.
+         */
+
+        let file = file.as_ref();
+        let contents = fs::read_to_string(file)
+            .map_err(|error| format!("Unable to read {}: {error}.", file.display()))?;
+
+        let mut priorities: HashMap<char, usize> = ALPHABET_LOWERCASE
+            .iter()
+            .chain(ALPHABET_UPPERCASE.iter())
+            .map(|item| (*item, AlphabetPriority.get_priority(*item).unwrap()))
+            .collect();
+
+        for (idx, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid_line = || {
+                format!(
+                    "Invalid priority mapping on line {} of {}: {line:?}.",
+                    idx + 1,
+                    file.display()
+                )
+            };
+
+            let (item, priority) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(invalid_line)?;
+
+            let mut item_chars = item.chars();
+            let item = match (item_chars.next(), item_chars.next()) {
+                (Some(item), None) => item,
+                _ => return Err(invalid_line()),
+            };
+            let priority = priority
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| invalid_line())?;
+
+            priorities.insert(item, priority);
+        }
+
+        return Ok(MappedPriority { priorities });
+    }
+}
+
+impl PriorityScheme for MappedPriority {
+    fn get_priority(&self, item: char) -> Option<usize> {
+        return self.priorities.get(&item).copied();
+    }
+}
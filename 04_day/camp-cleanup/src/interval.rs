@@ -0,0 +1,106 @@
+use std::fmt;
+
+/// An inclusive range of sectors. All operations only look at the bounds,
+/// so they take constant time however many sectors the interval spans.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Interval {
+    start: u64,
+    end: u64,
+}
+
+impl Interval {
+    pub fn new(start: u64, end: u64) -> Result<Interval, String> {
+        if start > end {
+            return Err(format!(
+                "Reversed range {start}-{end}: the lower bound must come first."
+            ));
+        }
+
+        return Ok(Interval { start, end });
+    }
+
+    pub fn parse(range: &str) -> Result<Interval, String> {
+        /*!
+         * Parse a range written as `<start>-<end>`, e.g. `2-4`.
+         */
+
+        let (start, end) = range
+            .trim()
+            .split_once('-')
+            .ok_or(format!("Invalid range {range:?}: expected <start>-<end>."))?;
+
+        let parse_bound = |bound: &str| {
+            bound
+                .trim()
+                .parse::<u64>()
+                .map_err(|error| format!("Invalid bound {bound:?} in range {range:?}: {error}."))
+        };
+
+        return Interval::new(parse_bound(start)?, parse_bound(end)?);
+    }
+
+    pub fn start(&self) -> u64 {
+        return self.start;
+    }
+
+    pub fn end(&self) -> u64 {
+        return self.end;
+    }
+
+    pub fn len(&self) -> u128 {
+        /*!
+         * Number of sectors in the interval. This is a u128, as the interval
+         * `0-18446744073709551615` holds one more sector than fits in a u64.
+         */
+
+        return (self.end - self.start) as u128 + 1;
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        return self.start <= other.end && other.start <= self.end;
+    }
+
+    pub fn contains(&self, other: &Interval) -> bool {
+        return self.start <= other.start && other.end <= self.end;
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        if !self.overlaps(other) {
+            return None;
+        }
+
+        return Some(Interval {
+            start: self.start.max(other.start),
+            end: self.end.min(other.end),
+        });
+    }
+
+    pub fn union(&self, other: &Interval) -> Option<Interval> {
+        /*!
+         * The interval covering both intervals, if they overlap or are
+         * adjacent. Otherwise the union is not an interval, and None is
+         * returned.
+         */
+
+        let (first, second) = if self.start <= other.start {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
+        if first.end < u64::MAX && first.end + 1 < second.start {
+            return None;
+        }
+
+        return Some(Interval {
+            start: first.start,
+            end: first.end.max(second.end),
+        });
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        return write!(formatter, "{}-{}", self.start, self.end);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::interval::Interval;
+
+/// A set of sectors, stored as sorted, disjoint and non-adjacent intervals.
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+/// A stretch of sectors which are all cleaned by the same number of elves.
+pub struct CoverageSegment {
+    pub interval: Interval,
+    pub num_elves: usize,
+}
+
+impl IntervalSet {
+    pub fn from_intervals(intervals: &[Interval]) -> IntervalSet {
+        /*!
+         * Merge the given intervals, in any order, into a set.
+         */
+
+        let mut sorted_intervals = intervals.to_vec();
+        sorted_intervals.sort();
+
+        let mut merged: Vec<Interval> = Vec::new();
+        for interval in sorted_intervals {
+            if let Some(last) = merged.last_mut() {
+                if let Some(union) = last.union(&interval) {
+                    *last = union;
+                    continue;
+                }
+            }
+
+            merged.push(interval);
+        }
+
+        return IntervalSet { intervals: merged };
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        return &self.intervals;
+    }
+
+    pub fn len(&self) -> u128 {
+        /*!
+         * Number of sectors in the set.
+         */
+
+        return self.intervals.iter().map(Interval::len).sum();
+    }
+
+    pub fn contains(&self, interval: &Interval) -> bool {
+        /*!
+         * Whether every sector of the interval is in the set. As the set's
+         * intervals are disjoint and not adjacent, the interval has to lie
+         * within a single one of them.
+         */
+
+        let candidate_idx = self
+            .intervals
+            .partition_point(|set_interval| set_interval.start() <= interval.start());
+
+        return candidate_idx > 0 && self.intervals[candidate_idx - 1].contains(interval);
+    }
+
+    pub fn get_gaps(&self) -> Vec<Interval> {
+        /*!
+         * The sectors between the first and last sector of the set which are
+         * not in it.
+         */
+
+        return self
+            .intervals
+            .windows(2)
+            .map(|pair| Interval::new(pair[0].end() + 1, pair[1].start() - 1).unwrap())
+            .collect();
+    }
+}
+
+pub fn get_coverage_segments(intervals: &[Interval]) -> Vec<CoverageSegment> {
+    /*!
+     * Sweep over the start and end points of the intervals, splitting the
+     * covered sectors into segments cleaned by the same number of elves.
+     * Sectors nobody cleans are left out.
+     */
+
+    // an interval ends just after its last sector, which for u64::MAX is
+    // beyond u64, so the sweep works with u128 positions
+    let mut events: Vec<(u128, isize)> = Vec::with_capacity(intervals.len() * 2);
+    for interval in intervals {
+        events.push((interval.start() as u128, 1));
+        events.push((interval.end() as u128 + 1, -1));
+    }
+    events.sort();
+
+    let mut segments: Vec<CoverageSegment> = Vec::new();
+    let mut num_elves: isize = 0;
+
+    for (event_idx, (position, change)) in events.iter().enumerate() {
+        num_elves += change;
+
+        let next_position = match events.get(event_idx + 1) {
+            Some((next_position, _change)) => *next_position,
+            None => break,
+        };
+
+        if num_elves > 0 && next_position > *position {
+            segments.push(CoverageSegment {
+                interval: Interval::new(*position as u64, (next_position - 1) as u64).unwrap(),
+                num_elves: num_elves as usize,
+            });
+        }
+    }
+
+    return segments;
+}
+
+pub fn render_coverage_map(segments: &[CoverageSegment], width: usize) -> (String, usize) {
+    /*!
+     * Draw the coverage as a single row of at most `width` characters, each
+     * standing for an equal share of the sectors between the first and last
+     * covered sector. A character shows the most elves cleaning any sector in
+     * its share: `.` for none, or a digit from 1 to 9. When more than nine
+     * elves clean the same sector, each digit stands for a number of elves,
+     * which is returned along with the map.
+     */
+
+    let (Some(first), Some(last)) = (segments.first(), segments.last()) else {
+        return (String::new(), 1);
+    };
+
+    let span_start = first.interval.start() as u128;
+    let span_len = last.interval.end() as u128 - span_start + 1;
+    let width = (width as u128).min(span_len).max(1);
+
+    let mut max_elves = vec![0; width as usize];
+    for segment in segments {
+        let first_column = (segment.interval.start() as u128 - span_start) * width / span_len;
+        let last_column = (segment.interval.end() as u128 - span_start) * width / span_len;
+
+        for column in first_column..=last_column {
+            let column_max = &mut max_elves[column as usize];
+            *column_max = (*column_max).max(segment.num_elves);
+        }
+    }
+
+    let elves_per_step = max_elves.iter().max().unwrap().div_ceil(9).max(1);
+
+    let coverage_map = max_elves
+        .iter()
+        .map(|num_elves| match num_elves {
+            0 => '.',
+            _ => char::from_digit(num_elves.div_ceil(elves_per_step) as u32, 10).unwrap(),
+        })
+        .collect();
+
+    return (coverage_map, elves_per_step);
+}
@@ -0,0 +1,96 @@
+use crate::interval::Interval;
+
+/// Identifies one elf's assignment: the record it is listed in and the
+/// elf's position within the record, both counted from 1.
+#[derive(Clone, Copy, Debug)]
+pub struct AssignmentId {
+    pub record: usize,
+    pub elf: usize,
+}
+
+/// A static interval tree over the assignments. The assignments are kept
+/// sorted by start, forming an implicit balanced binary search tree in which
+/// each node also knows the highest end in its subtree, so that whole
+/// subtrees ending before the queried range can be skipped.
+pub struct IntervalTree {
+    entries: Vec<(Interval, AssignmentId)>,
+    subtree_max_ends: Vec<u64>,
+}
+
+impl IntervalTree {
+    pub fn new(assignments: Vec<(Interval, AssignmentId)>) -> IntervalTree {
+        let mut entries = assignments;
+        entries.sort_by_key(|(interval, _id)| *interval);
+
+        let mut tree = IntervalTree {
+            subtree_max_ends: vec![0; entries.len()],
+            entries,
+        };
+        tree.compute_max_ends(0, tree.entries.len());
+
+        return tree;
+    }
+
+    fn compute_max_ends(&mut self, lo: usize, hi: usize) -> u64 {
+        /*!
+         * Fill in the highest end of the subtree over entries[lo..hi], whose
+         * root is the middle entry, and return it.
+         */
+
+        if lo >= hi {
+            return 0;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+        let max_end = self.entries[mid]
+            .0
+            .end()
+            .max(self.compute_max_ends(lo, mid))
+            .max(self.compute_max_ends(mid + 1, hi));
+        self.subtree_max_ends[mid] = max_end;
+
+        return max_end;
+    }
+
+    pub fn query(&self, range: &Interval) -> Vec<(Interval, AssignmentId)> {
+        /*!
+         * All assignments sharing at least one sector with the given range,
+         * ordered by their start. Takes O(log n + k) time for k results.
+         */
+
+        let mut found: Vec<(Interval, AssignmentId)> = Vec::new();
+        self.collect_overlapping(0, self.entries.len(), range, &mut found);
+
+        return found;
+    }
+
+    fn collect_overlapping(
+        &self,
+        lo: usize,
+        hi: usize,
+        range: &Interval,
+        found: &mut Vec<(Interval, AssignmentId)>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+        if self.subtree_max_ends[mid] < range.start() {
+            return;
+        }
+
+        self.collect_overlapping(lo, mid, range, found);
+
+        let (interval, id) = &self.entries[mid];
+        if interval.start() > range.end() {
+            // everything to the right starts even later
+            return;
+        }
+        if interval.overlaps(range) {
+            found.push((*interval, *id));
+        }
+
+        self.collect_overlapping(mid + 1, hi, range, found);
+    }
+}
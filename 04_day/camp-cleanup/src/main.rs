@@ -1,27 +1,449 @@
-use csv;
-use std::collections::hash_set::HashSet;
+#![allow(clippy::needless_return)]
+
+mod interval;
+mod interval_set;
+mod interval_tree;
+mod optimiser;
+mod render;
+
+use std::env;
+use std::fmt;
+use std::fs;
 use std::fs::File;
-use std::ops::RangeInclusive;
 use std::path::Path;
 
+use interval::Interval;
+use interval_set::IntervalSet;
+use interval_tree::{AssignmentId, IntervalTree};
+
+/// Marks an elf with no sectors to clean in the data file.
+const IDLE_ELF: &str = "-";
+
 fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
     let mut sector_duties = get_file_reader("data/data.csv");
 
-    let mut range_evaluations: Vec<bool> = Vec::new();
-    for elf_pair in sector_duties.records() {
-        if let Ok(sectors) = elf_pair {
-            let elf_sectors = parse_record(sectors);
-            let is_fully_contained = are_ranges_overlapping(elf_sectors);
-            range_evaluations.push(is_fully_contained);
+    let mut elf_groups: Vec<Vec<Option<Interval>>> = Vec::new();
+    for (record_idx, sectors) in sector_duties.records().flatten().enumerate() {
+        match parse_record(sectors) {
+            Ok(elf_sectors) => elf_groups.push(elf_sectors),
+            Err(message) => {
+                eprintln!("Record {}: {message}", record_idx + 1);
+                std::process::exit(1);
+            }
         }
     }
 
-    let count_fully_contained: usize = range_evaluations
-        .iter()
-        .map(|contained_eval| *contained_eval as usize)
-        .sum();
+    match args.first().map(String::as_str) {
+        Some("coverage") => {
+            run_coverage_analysis(&elf_groups, &args[1..]);
+            return;
+        }
+        Some("query") => {
+            run_query(&elf_groups, &args[1..]);
+            return;
+        }
+        Some("render") => {
+            run_render(&elf_groups, &args[1..]);
+            return;
+        }
+        Some("optimise") => {
+            run_optimiser(&elf_groups, get_option_value(&args, "--output"));
+            return;
+        }
+        _ => {}
+    }
+
+    let show_records = args.iter().any(|arg| arg == "--records");
+
+    let mut count_fully_contained = 0;
+    let mut count_overlapping = 0;
+    let mut count_redundant = 0;
+    for (record_idx, elf_sectors) in elf_groups.iter().enumerate() {
+        let pair_relations = classify_record_pairs(elf_sectors);
+        let redundant_elves = find_redundant_ranges(elf_sectors);
+
+        if show_records {
+            print_record_summary(
+                record_idx + 1,
+                elf_sectors,
+                &pair_relations,
+                &redundant_elves,
+            );
+        }
+
+        if pair_relations
+            .iter()
+            .any(|(_elves, relation)| relation.is_containment())
+        {
+            count_fully_contained += 1;
+        }
+        if pair_relations
+            .iter()
+            .any(|(_elves, relation)| *relation != RangePairRelation::Disjoint)
+        {
+            count_overlapping += 1;
+        }
+        if !redundant_elves.is_empty() {
+            count_redundant += 1;
+        }
+    }
 
     println!("The number of records where one range is fully contained in another is {count_fully_contained}.");
+    println!("The number of records where the ranges overlap is {count_overlapping}.");
+
+    // with two elves, a redundant range is the same as a contained one
+    if elf_groups
+        .iter()
+        .any(|elf_sectors| elf_sectors.iter().flatten().count() > 2)
+    {
+        println!("The number of records where a range is covered by the other ranges is {count_redundant}.");
+    }
+}
+
+fn get_option_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    /*!
+     * Fetch the value following the given flag in the command line arguments.
+     */
+
+    let flag_idx = args.iter().position(|arg| arg == flag)?;
+
+    return args.get(flag_idx + 1).map(String::as_str);
+}
+
+fn run_coverage_analysis(elf_groups: &[Vec<Option<Interval>>], args: &[String]) {
+    /*!
+     * Merge the ranges of all elves and report the covered sectors, the gaps
+     * nobody cleans, and the sectors cleaned by more than `--max-elves`
+     * elves (one by default), followed by a coverage map.
+     */
+
+    let max_elves = get_option_value(args, "--max-elves")
+        .map(|value| {
+            value
+                .parse::<usize>()
+                .unwrap_or_else(|_| panic!("Invalid value for --max-elves: {value}."))
+        })
+        .unwrap_or(1);
+
+    let all_ranges: Vec<Interval> = elf_groups.iter().flatten().flatten().copied().collect();
+
+    let covered = IntervalSet::from_intervals(&all_ranges);
+    let covered_ranges: Vec<String> = covered
+        .intervals()
+        .iter()
+        .map(Interval::to_string)
+        .collect();
+    println!(
+        "{} sectors are covered: {}",
+        covered.len(),
+        covered_ranges.join(", ")
+    );
+
+    let gaps = covered.get_gaps();
+    let gap_ranges: Vec<String> = gaps.iter().map(Interval::to_string).collect();
+    println!(
+        "{} sectors are not cleaned by anybody: {}",
+        gaps.iter().map(Interval::len).sum::<u128>(),
+        gap_ranges.join(", ")
+    );
+
+    let segments = interval_set::get_coverage_segments(&all_ranges);
+    let crowded_ranges: Vec<Interval> = segments
+        .iter()
+        .filter(|segment| segment.num_elves > max_elves)
+        .map(|segment| segment.interval)
+        .collect();
+    let crowded = IntervalSet::from_intervals(&crowded_ranges);
+    let crowded_ranges: Vec<String> = crowded
+        .intervals()
+        .iter()
+        .map(Interval::to_string)
+        .collect();
+    println!(
+        "{} sectors are cleaned by more than {max_elves} elves: {}",
+        crowded.len(),
+        crowded_ranges.join(", ")
+    );
+
+    if let (Some(first), Some(last)) = (covered.intervals().first(), covered.intervals().last()) {
+        println!();
+        let (coverage_map, elves_per_step) = interval_set::render_coverage_map(&segments, 100);
+        println!(
+            "Coverage map of sectors {}-{} (one digit step = {elves_per_step} elves):",
+            first.start(),
+            last.end()
+        );
+        println!("{coverage_map}");
+    }
+}
+
+fn run_query(elf_groups: &[Vec<Option<Interval>>], args: &[String]) {
+    /*!
+     * List the assignments covering a sector (`query point <sector>`) or
+     * sharing any sector with a range (`query range <start>-<end>`).
+     */
+
+    let query_range = match (args.first().map(String::as_str), args.get(1)) {
+        (Some("point"), Some(sector)) => match sector.parse::<u64>() {
+            Ok(sector) => Interval::new(sector, sector),
+            Err(error) => Err(format!("Invalid sector {sector:?}: {error}.")),
+        },
+        (Some("range"), Some(range)) => Interval::parse(range),
+        _ => Err(String::from(
+            "Usage: query point <sector> | query range <start>-<end>",
+        )),
+    };
+    let query_range = query_range.unwrap_or_else(|message| {
+        eprintln!("{message}");
+        std::process::exit(1);
+    });
+
+    let assignments: Vec<(Interval, AssignmentId)> = elf_groups
+        .iter()
+        .enumerate()
+        .flat_map(|(record_idx, elf_sectors)| {
+            elf_sectors
+                .iter()
+                .enumerate()
+                .filter_map(move |(elf_idx, range)| {
+                    let id = AssignmentId {
+                        record: record_idx + 1,
+                        elf: elf_idx + 1,
+                    };
+                    range.map(|range| (range, id))
+                })
+        })
+        .collect();
+    let assignment_tree = IntervalTree::new(assignments);
+
+    let mut found = assignment_tree.query(&query_range);
+    found.sort_by_key(|(_interval, id)| (id.record, id.elf));
+
+    println!("{} assignments cover {query_range}:", found.len());
+    for (interval, id) in found {
+        println!("    record {}, elf {}: {interval}", id.record, id.elf);
+    }
+}
+
+fn run_render(elf_groups: &[Vec<Option<Interval>>], args: &[String]) {
+    /*!
+     * Draw the ranges of every record, or only of `--record <n>`, as aligned
+     * rows at most `--width` characters wide (100 by default), and write them
+     * as an SVG image to `--svg <file>` if given.
+     */
+
+    let parse_option = |flag: &str| {
+        get_option_value(args, flag).map(|value| {
+            value
+                .parse::<usize>()
+                .unwrap_or_else(|_| panic!("Invalid value for {flag}: {value}."))
+        })
+    };
+    let width = parse_option("--width").unwrap_or(100);
+
+    let records: Vec<(usize, &[Option<Interval>])> = match parse_option("--record") {
+        Some(record_number) if (1..=elf_groups.len()).contains(&record_number) => {
+            vec![(record_number, &elf_groups[record_number - 1])]
+        }
+        Some(record_number) => {
+            eprintln!(
+                "Record {record_number} does not exist, there are {} records.",
+                elf_groups.len()
+            );
+            std::process::exit(1);
+        }
+        None => elf_groups
+            .iter()
+            .enumerate()
+            .map(|(record_idx, elf_sectors)| (record_idx + 1, elf_sectors.as_slice()))
+            .collect(),
+    };
+
+    let all_ranges: Vec<Interval> = records
+        .iter()
+        .flat_map(|(_record_number, elf_sectors)| elf_sectors.iter().flatten().copied())
+        .collect();
+    let (Some(first), Some(last)) = (
+        all_ranges.iter().map(Interval::start).min(),
+        all_ranges.iter().map(Interval::end).max(),
+    ) else {
+        return;
+    };
+    let span = Interval::new(first, last).unwrap();
+
+    let scale = render::Scale::new(span, width);
+    if scale.sectors_per_column() > 1 {
+        println!(
+            "Sectors {span}, one column = {} sectors:",
+            scale.sectors_per_column()
+        );
+    } else {
+        println!("Sectors {span}:");
+    }
+
+    for (record_number, elf_sectors) in &records {
+        println!();
+        println!("Record {record_number}:");
+        for row in render::render_record(elf_sectors, &scale) {
+            println!("{row}");
+        }
+    }
+
+    if let Some(svg_file) = get_option_value(args, "--svg") {
+        fs::write(svg_file, render::render_svg(&records, span)).unwrap();
+        println!();
+        println!("SVG written to {svg_file}.");
+    }
+}
+
+fn run_optimiser(elf_groups: &[Vec<Option<Interval>>], output_file: Option<&str>) {
+    /*!
+     * Trims the assignments of each record so that no sector is cleaned
+     * twice, prints the changed records, and writes out the corrected
+     * assignments, either to `output_file` or after the changes. Elves left
+     * without any sectors keep their column, marked as idle with `-`.
+     */
+
+    let mut corrected_records: Vec<String> = Vec::new();
+    let mut num_changed = 0;
+    let mut num_unplanned = 0;
+
+    for (record_idx, elf_sectors) in elf_groups.iter().enumerate() {
+        // idle elves take no part in the plan, but keep their place
+        let (assigned_elves, assigned_ranges): (Vec<usize>, Vec<Interval>) = elf_sectors
+            .iter()
+            .enumerate()
+            .filter_map(|(elf_idx, range)| range.map(|range| (elf_idx, range)))
+            .unzip();
+
+        match optimiser::plan_record(&assigned_ranges) {
+            Ok(plan) => {
+                let mut trimmed: Vec<Option<Interval>> = vec![None; elf_sectors.len()];
+                for (elf_idx, assignment) in assigned_elves.iter().zip(plan.assignments) {
+                    trimmed[*elf_idx] = assignment;
+                }
+
+                let changed_elves: Vec<usize> = (0..elf_sectors.len())
+                    .filter(|elf_idx| trimmed[*elf_idx] != elf_sectors[*elf_idx])
+                    .collect();
+                if !changed_elves.is_empty() {
+                    let changes: Vec<String> = changed_elves
+                        .iter()
+                        .map(|elf_idx| match trimmed[*elf_idx] {
+                            Some(trimmed) => format!("elf {} {trimmed}", elf_idx + 1),
+                            None => format!("elf {} no longer needed", elf_idx + 1),
+                        })
+                        .collect();
+                    println!(
+                        "Record {}: {} -> {} ({})",
+                        record_idx + 1,
+                        format_record(elf_sectors),
+                        format_record(&trimmed),
+                        changes.join(", ")
+                    );
+                }
+
+                num_changed += changed_elves.len();
+                corrected_records.push(format_record(&trimmed));
+            }
+            Err(reason) => {
+                println!("Record {}: cannot be planned, {reason}", record_idx + 1);
+                num_unplanned += 1;
+                corrected_records.push(format_record(elf_sectors));
+            }
+        }
+    }
+
+    println!("{num_changed} assignments changed; {num_unplanned} records could not be planned.");
+
+    match output_file {
+        Some(output_file) => {
+            fs::write(output_file, corrected_records.join("\n") + "\n").unwrap();
+            println!("Corrected assignments written to {output_file}.");
+        }
+        None => {
+            println!();
+            for record in corrected_records {
+                println!("{record}");
+            }
+        }
+    }
+}
+
+fn format_record(elf_sectors: &[Option<Interval>]) -> String {
+    /*!
+     * Write the ranges of a record as they appear in the data file, with `-`
+     * for idle elves.
+     */
+
+    let ranges: Vec<String> = elf_sectors
+        .iter()
+        .map(|range| match range {
+            Some(range) => range.to_string(),
+            None => String::from(IDLE_ELF),
+        })
+        .collect();
+
+    return ranges.join(",");
+}
+
+fn print_record_summary(
+    record_number: usize,
+    elf_sectors: &[Option<Interval>],
+    pair_relations: &[((usize, usize), RangePairRelation)],
+    redundant_elves: &[usize],
+) {
+    /*!
+     * Print how the ranges of a record relate, how many sectors they share,
+     * and how many sectors they cover together. Records with two elves get a
+     * single line; larger ones get a line per pair of elves, and a list of
+     * ranges which the other ranges already cover. Idle elves are shown as
+     * `-` and left out of the comparisons.
+     */
+
+    let ranges = format_record(elf_sectors);
+
+    let describe_pair = |elf_a: usize, elf_b: usize, relation: &RangePairRelation| {
+        let (range_1, range_2) = (elf_sectors[elf_a].unwrap(), elf_sectors[elf_b].unwrap());
+
+        let shared_sectors = match range_1.intersection(&range_2) {
+            Some(shared) => format!("{} shared sectors ({shared})", shared.len()),
+            None => String::from("no shared sectors"),
+        };
+        let covered_sectors = match range_1.union(&range_2) {
+            Some(covered) => covered.len(),
+            None => range_1.len() + range_2.len(),
+        };
+
+        return format!("{relation}, {shared_sectors}, {covered_sectors} sectors covered");
+    };
+
+    if let [((elf_a, elf_b), relation)] = pair_relations {
+        println!(
+            "Record {record_number}: {ranges} {}",
+            describe_pair(*elf_a, *elf_b, relation)
+        );
+        return;
+    }
+
+    println!("Record {record_number}: {ranges}");
+    for ((elf_a, elf_b), relation) in pair_relations {
+        println!(
+            "    elves {} and {}: {}",
+            elf_a + 1,
+            elf_b + 1,
+            describe_pair(*elf_a, *elf_b, relation)
+        );
+    }
+    if !redundant_elves.is_empty() {
+        let redundant: Vec<String> = redundant_elves
+            .iter()
+            .map(|elf_idx| format!("elf {} ({})", elf_idx + 1, elf_sectors[*elf_idx].unwrap()))
+            .collect();
+        println!("    covered by the others: {}", redundant.join(", "));
+    }
 }
 
 fn get_file_reader<P>(file: P) -> csv::Reader<File>
@@ -29,69 +451,157 @@ where
     P: AsRef<Path>,
 {
     /*!
-     * Read in the data file, returning a Reader object.
+     * Read in the data file, returning a Reader object. Records may list
+     * any number of ranges, so they are not required to be of equal length.
      */
 
     let csv_reader = csv::ReaderBuilder::new()
         .has_headers(false)
+        .flexible(true)
         .from_path(file)
         .unwrap();
 
     return csv_reader;
 }
 
-fn parse_record(record: csv::StringRecord) -> (RangeInclusive<usize>, RangeInclusive<usize>) {
+fn parse_record(record: csv::StringRecord) -> Result<Vec<Option<Interval>>, String> {
     /*!
-     * Takes each record from the CSV file, parses both fields and returns a
-     * tuple of range objects.
+     * Takes each record from the CSV file, parses every field and returns a
+     * vector of intervals, one per elf. An elf with nothing to clean, as the
+     * optimiser writes them, is listed as `-` and returned as None, so the
+     * other elves keep their places.
      */
-    let sectors_elf_1: Vec<String> = record
-        .get(0)
-        .unwrap()
-        .split('-')
-        .map(|part| part.to_string())
-        .collect();
-    let range_elf_1 = if let (Some(bound_lower), Some(bound_upper)) =
-        (sectors_elf_1.get(0), sectors_elf_1.get(1))
-    {
-        let bound_lower = bound_lower.parse::<usize>().unwrap();
-        let bound_upper = bound_upper.parse::<usize>().unwrap();
-        bound_lower..=bound_upper
-    } else {
-        panic!("Invalid range listed for elf 1.");
-    };
 
-    let sectors_elf_2: Vec<String> = record
-        .get(1)
-        .unwrap()
-        .split('-')
-        .map(|part| part.to_string())
-        .collect();
-    let range_elf_2 = if let (Some(bound_lower), Some(bound_upper)) =
-        (sectors_elf_2.get(0), sectors_elf_2.get(1))
-    {
-        let bound_lower = bound_lower.parse::<usize>().unwrap();
-        let bound_upper = bound_upper.parse::<usize>().unwrap();
-        bound_lower..=bound_upper
-    } else {
-        panic!("Invalid range listed for elf 2.");
-    };
+    let elf_sectors = record
+        .iter()
+        .enumerate()
+        .map(|(field_idx, field)| {
+            if field.trim() == IDLE_ELF {
+                return Ok(None);
+            }
+
+            Interval::parse(field).map(Some).map_err(|message| {
+                format!("Invalid range listed for elf {}: {message}", field_idx + 1)
+            })
+        })
+        .collect::<Result<Vec<Option<Interval>>, String>>()?;
 
-    return (range_elf_1, range_elf_2);
+    if elf_sectors.iter().all(Option::is_none) {
+        return Err(String::from("Expected at least one range, found none."));
+    }
+
+    return Ok(elf_sectors);
 }
 
-fn are_ranges_overlapping(range_pair: (RangeInclusive<usize>, RangeInclusive<usize>)) -> bool {
+fn are_ranges_overlapping(range_pair: &(Interval, Interval)) -> bool {
     /*!
      * Determines whether the two ranges overlap or not (irrespective of
      * whether the overlap is complete or not).
      */
 
-    let elems_range_1: HashSet<usize> = range_pair.0.collect();
-    let elems_range_2: HashSet<usize> = range_pair.1.collect();
+    return range_pair.0.overlaps(&range_pair.1);
+}
 
-    if elems_range_1.intersection(&elems_range_2).count() == 0 {
-        return false;
-    } else {
-        return true;
+fn classify_range_pair(range_pair: &(Interval, Interval)) -> RangePairRelation {
+    /*!
+     * Determines how the ranges of the two elves in a record relate to each
+     * other.
+     */
+
+    let first_in_second = range_pair.1.contains(&range_pair.0);
+    let second_in_first = range_pair.0.contains(&range_pair.1);
+
+    let relation = match (first_in_second, second_in_first) {
+        (true, true) => RangePairRelation::Equal,
+        (true, false) => RangePairRelation::FirstInSecond,
+        (false, true) => RangePairRelation::SecondInFirst,
+        (false, false) if are_ranges_overlapping(range_pair) => RangePairRelation::Overlapping,
+        (false, false) => RangePairRelation::Disjoint,
+    };
+
+    return relation;
+}
+
+fn classify_record_pairs(
+    elf_sectors: &[Option<Interval>],
+) -> Vec<((usize, usize), RangePairRelation)> {
+    /*!
+     * Classifies every pair of ranges in a record, returning the indices of
+     * the two elves along with how their ranges relate. Idle elves are
+     * skipped.
+     */
+
+    let mut pair_relations = Vec::new();
+
+    for elf_a in 0..elf_sectors.len() {
+        for elf_b in (elf_a + 1)..elf_sectors.len() {
+            if let (Some(range_a), Some(range_b)) = (elf_sectors[elf_a], elf_sectors[elf_b]) {
+                let relation = classify_range_pair(&(range_a, range_b));
+                pair_relations.push(((elf_a, elf_b), relation));
+            }
+        }
+    }
+
+    return pair_relations;
+}
+
+fn find_redundant_ranges(elf_sectors: &[Option<Interval>]) -> Vec<usize> {
+    /*!
+     * Finds the elves whose ranges are entirely covered by the ranges of the
+     * other elves in the record, so that their work is redundant. This also
+     * catches ranges covered by several others together, which no single
+     * pairwise comparison would.
+     */
+
+    return (0..elf_sectors.len())
+        .filter(|elf_idx| {
+            let Some(range) = elf_sectors[*elf_idx] else {
+                return false;
+            };
+
+            let other_ranges: Vec<Interval> = elf_sectors
+                .iter()
+                .enumerate()
+                .filter(|(other_idx, _range)| other_idx != elf_idx)
+                .filter_map(|(_other_idx, range)| *range)
+                .collect();
+
+            IntervalSet::from_intervals(&other_ranges).contains(&range)
+        })
+        .collect();
+}
+
+#[derive(PartialEq, Debug)]
+enum RangePairRelation {
+    Disjoint,
+    /// The ranges overlap, but neither contains the other.
+    Overlapping,
+    FirstInSecond,
+    SecondInFirst,
+    Equal,
+}
+
+impl RangePairRelation {
+    fn is_containment(&self) -> bool {
+        return matches!(
+            self,
+            RangePairRelation::FirstInSecond
+                | RangePairRelation::SecondInFirst
+                | RangePairRelation::Equal
+        );
+    }
+}
+
+impl fmt::Display for RangePairRelation {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let description = match self {
+            RangePairRelation::Disjoint => "disjoint",
+            RangePairRelation::Overlapping => "overlapping",
+            RangePairRelation::FirstInSecond => "A \u{2286} B",
+            RangePairRelation::SecondInFirst => "B \u{2286} A",
+            RangePairRelation::Equal => "equal",
+        };
+
+        return write!(formatter, "{description}");
     }
 }
@@ -0,0 +1,121 @@
+use crate::interval::Interval;
+use crate::interval_set::IntervalSet;
+
+/// Largest record the optimiser plans for, as it tries every subset of the
+/// record's assignments.
+const MAX_PLANNED_ELVES: usize = 20;
+
+/// The trimmed assignments of a record, in the order of its elves. Elves
+/// whose work is all done by the others get no sectors.
+pub struct RecordPlan {
+    pub assignments: Vec<Option<Interval>>,
+}
+
+pub fn plan_record(elf_sectors: &[Interval]) -> Result<RecordPlan, String> {
+    /*!
+     * Trims the ranges of a record so that no sector is cleaned twice, while
+     * every sector covered before is still covered, changing as few
+     * assignments as possible. Each trimmed range lies within the original
+     * one, so nobody is sent to a sector they were not assigned.
+     *
+     * The assignments left alone must not overlap each other, so every subset
+     * of pairwise disjoint ranges is tried, largest first, until the other
+     * ranges can be trimmed to cover whatever the subset leaves uncovered.
+     */
+
+    if elf_sectors.len() > MAX_PLANNED_ELVES {
+        return Err(format!(
+            "{} elves are too many to plan for, at most {MAX_PLANNED_ELVES} are supported",
+            elf_sectors.len()
+        ));
+    }
+
+    let mut subsets: Vec<u32> = (0..1u32 << elf_sectors.len()).collect();
+    subsets.sort_by_key(|subset| std::cmp::Reverse(subset.count_ones()));
+
+    for subset in subsets {
+        let kept: Vec<usize> = (0..elf_sectors.len())
+            .filter(|elf_idx| subset & (1 << elf_idx) != 0)
+            .collect();
+
+        let is_disjoint = kept.iter().enumerate().all(|(kept_idx, elf_a)| {
+            kept[kept_idx + 1..].iter().all(|elf_b| {
+                !crate::are_ranges_overlapping(&(elf_sectors[*elf_a], elf_sectors[*elf_b]))
+            })
+        });
+        if !is_disjoint {
+            continue;
+        }
+
+        if let Some(assignments) = trim_remaining_ranges(elf_sectors, &kept) {
+            return Ok(RecordPlan { assignments });
+        }
+    }
+
+    // trimming every range is always possible, e.g. by letting each range
+    // start after the ones before it end, so the empty subset succeeds
+    unreachable!("no plan found for {elf_sectors:?}");
+}
+
+fn trim_remaining_ranges(
+    elf_sectors: &[Interval],
+    kept: &[usize],
+) -> Option<Vec<Option<Interval>>> {
+    /*!
+     * Keeps the given ranges as they are and trims the others to cover the
+     * rest of the record's sectors without overlapping, if that is possible.
+     *
+     * The sectors are swept from left to right. The first uncovered sector
+     * goes to the unused range containing it which ends soonest, as a range
+     * reaching further may still be needed later, and that range takes as
+     * many of the following sectors as it can.
+     */
+
+    let mut assignments: Vec<Option<Interval>> = vec![None; elf_sectors.len()];
+    let mut kept_ranges: Vec<Interval> = Vec::new();
+    for elf_idx in kept {
+        assignments[*elf_idx] = Some(elf_sectors[*elf_idx]);
+        kept_ranges.push(elf_sectors[*elf_idx]);
+    }
+    kept_ranges.sort();
+
+    let mut unused: Vec<usize> = (0..elf_sectors.len())
+        .filter(|elf_idx| !kept.contains(elf_idx))
+        .collect();
+
+    for covered in IntervalSet::from_intervals(elf_sectors).intervals() {
+        let mut sector = Some(covered.start());
+
+        while let Some(current) = sector.filter(|current| *current <= covered.end()) {
+            if let Some(kept_range) = kept_ranges
+                .iter()
+                .find(|range| range.start() <= current && current <= range.end())
+            {
+                sector = kept_range.end().checked_add(1);
+                continue;
+            }
+
+            let (unused_idx, elf_idx) = unused
+                .iter()
+                .enumerate()
+                .filter(|(_unused_idx, elf_idx)| {
+                    let range = elf_sectors[**elf_idx];
+                    range.start() <= current && current <= range.end()
+                })
+                .min_by_key(|(_unused_idx, elf_idx)| elf_sectors[**elf_idx].end())
+                .map(|(unused_idx, elf_idx)| (unused_idx, *elf_idx))?;
+            unused.remove(unused_idx);
+
+            // stop short of the next kept range, if there is one in reach
+            let mut end = elf_sectors[elf_idx].end().min(covered.end());
+            if let Some(next_kept) = kept_ranges.iter().find(|range| range.start() > current) {
+                end = end.min(next_kept.start() - 1);
+            }
+
+            assignments[elf_idx] = Some(Interval::new(current, end).unwrap());
+            sector = end.checked_add(1);
+        }
+    }
+
+    return Some(assignments);
+}
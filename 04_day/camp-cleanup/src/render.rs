@@ -0,0 +1,202 @@
+use std::fmt::Write;
+
+use crate::interval::Interval;
+use crate::interval_set::{self, IntervalSet};
+
+/// Sectors spread over the columns of a drawing. When the sectors fit, each
+/// column is one sector; otherwise each stands for an equal share of them.
+pub struct Scale {
+    span: Interval,
+    width: u128,
+}
+
+impl Scale {
+    pub fn new(span: Interval, max_width: usize) -> Scale {
+        return Scale {
+            span,
+            width: (max_width as u128).min(span.len()).max(1),
+        };
+    }
+
+    pub fn sectors_per_column(&self) -> u128 {
+        return self.span.len().div_ceil(self.width);
+    }
+
+    fn get_columns(&self, interval: &Interval) -> (usize, usize) {
+        /*!
+         * The first and last column covering any sector of the interval.
+         */
+
+        let span_start = self.span.start() as u128;
+        let get_column = |sector: u64| (sector as u128 - span_start) * self.width / self.span.len();
+
+        return (
+            get_column(interval.start()) as usize,
+            get_column(interval.end()) as usize,
+        );
+    }
+}
+
+fn get_overlaps(elf_sectors: &[Option<Interval>]) -> Vec<Interval> {
+    /*!
+     * The sectors of a record cleaned by more than one elf.
+     */
+
+    let ranges: Vec<Interval> = elf_sectors.iter().flatten().copied().collect();
+
+    let segments: Vec<Interval> = interval_set::get_coverage_segments(&ranges)
+        .into_iter()
+        .filter(|segment| segment.num_elves > 1)
+        .map(|segment| segment.interval)
+        .collect();
+
+    // neighbouring segments differ only in how many elves clean them
+    return IntervalSet::from_intervals(&segments).intervals().to_vec();
+}
+
+pub fn render_record(elf_sectors: &[Option<Interval>], scale: &Scale) -> Vec<String> {
+    /*!
+     * Draw each range of a record as a row in the style of the puzzle, e.g.
+     * `.234.....` for `2-4`, followed by a row marking the sectors cleaned by
+     * more than one elf with `^`. When a column stands for several sectors,
+     * ranges are drawn with `#` instead of sector digits. Idle elves get an
+     * empty row.
+     */
+
+    let width = scale.width as usize;
+    let mut rows: Vec<String> = Vec::new();
+
+    for range in elf_sectors {
+        let mut row = vec!['.'; width];
+        let Some(range) = range else {
+            rows.push(format!(
+                "{}  {}",
+                row.iter().collect::<String>(),
+                crate::IDLE_ELF
+            ));
+            continue;
+        };
+        let (first_column, last_column) = scale.get_columns(range);
+
+        for (column, cell) in row
+            .iter_mut()
+            .enumerate()
+            .take(last_column + 1)
+            .skip(first_column)
+        {
+            *cell = match scale.sectors_per_column() {
+                1 => {
+                    let sector = scale.span.start() + column as u64;
+                    char::from_digit((sector % 10) as u32, 10).unwrap()
+                }
+                _ => '#',
+            };
+        }
+
+        rows.push(format!("{}  {range}", row.iter().collect::<String>()));
+    }
+
+    let overlaps = get_overlaps(elf_sectors);
+    let mut overlap_row = vec![' '; width];
+    for overlap in &overlaps {
+        let (first_column, last_column) = scale.get_columns(overlap);
+        overlap_row[first_column..=last_column].fill('^');
+    }
+
+    let overlap_ranges: Vec<String> = overlaps.iter().map(Interval::to_string).collect();
+    let overlap_label = match overlap_ranges.is_empty() {
+        true => String::from("no overlap"),
+        false => format!("overlap {}", overlap_ranges.join(", ")),
+    };
+    rows.push(format!(
+        "{}  {overlap_label}",
+        overlap_row.iter().collect::<String>()
+    ));
+
+    return rows;
+}
+
+pub fn render_svg(records: &[(usize, &[Option<Interval>])], span: Interval) -> String {
+    /*!
+     * Draw the records as an SVG image, one bar per range with the sectors
+     * cleaned by more than one elf of the record drawn in red. All records
+     * share the same horizontal scale, so their bars line up.
+     */
+
+    const LABEL_WIDTH: f64 = 90.0;
+    const PLOT_WIDTH: f64 = 800.0;
+    const ROW_HEIGHT: f64 = 14.0;
+    const RECORD_GAP: f64 = 10.0;
+
+    let span_start = span.start() as f64;
+    let sector_width = PLOT_WIDTH / span.len() as f64;
+    let get_x = |sector: u64| LABEL_WIDTH + (sector as f64 - span_start) * sector_width;
+
+    let num_rows: usize = records.iter().map(|(_number, ranges)| ranges.len()).sum();
+    let height = num_rows as f64 * ROW_HEIGHT + records.len() as f64 * RECORD_GAP + RECORD_GAP;
+    let width = LABEL_WIDTH + PLOT_WIDTH + 60.0;
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" font-family="monospace" font-size="10">"#
+    )
+    .unwrap();
+
+    let mut y = RECORD_GAP;
+    for (record_number, elf_sectors) in records {
+        writeln!(
+            svg,
+            r#"  <text x="4" y="{}">Record {record_number}</text>"#,
+            y + ROW_HEIGHT - 4.0
+        )
+        .unwrap();
+
+        let record_top = y;
+        for range in elf_sectors.iter() {
+            let label = match range {
+                Some(range) => range.to_string(),
+                None => String::from(crate::IDLE_ELF),
+            };
+
+            if let Some(range) = range {
+                let x = get_x(range.start());
+                let bar_width = range.len() as f64 * sector_width;
+                writeln!(
+                    svg,
+                    r##"  <rect x="{x:.2}" y="{}" width="{bar_width:.2}" height="{}" fill="#8aa9d6"/>"##,
+                    y + 2.0,
+                    ROW_HEIGHT - 4.0
+                )
+                .unwrap();
+            }
+            writeln!(
+                svg,
+                r#"  <text x="{}" y="{}">{label}</text>"#,
+                LABEL_WIDTH + PLOT_WIDTH + 6.0,
+                y + ROW_HEIGHT - 4.0
+            )
+            .unwrap();
+            y += ROW_HEIGHT;
+        }
+
+        // mark the overlaps across all rows of the record
+        for overlap in get_overlaps(elf_sectors) {
+            writeln!(
+                svg,
+                r##"  <rect x="{:.2}" y="{}" width="{:.2}" height="{}" fill="#d64545" fill-opacity="0.6"/>"##,
+                get_x(overlap.start()),
+                record_top + 2.0,
+                overlap.len() as f64 * sector_width,
+                y - record_top - 4.0
+            )
+            .unwrap();
+        }
+
+        y += RECORD_GAP;
+    }
+
+    svg.push_str("</svg>\n");
+
+    return svg;
+}
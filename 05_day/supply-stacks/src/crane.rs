@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use crate::CargoOperation;
+
+/// A model of crane, which decides how crates are carried between stacks.
+pub trait CraneModel {
+    fn name(&self) -> String;
+
+    /// Carry `quantity` crates from the top of the `from` stack onto the top
+    /// of the `to` stack. Stacks are keyed from 0, with the top crate last.
+    fn move_crates(
+        &self,
+        cargo_state: &mut HashMap<usize, Vec<String>>,
+        operation: &CargoOperation,
+    );
+}
+
+/// Lifts one crate at a time, so the moved crates end up in reverse order.
+pub struct CrateMover9000;
+
+/// Lifts all the moved crates at once, so they keep their order.
+pub struct CrateMover9001;
+
+/// Lifts at most `max_lift` crates at a time, each lift keeping its order.
+pub struct MaxLiftCrane {
+    pub max_lift: usize,
+}
+
+impl CraneModel for CrateMover9000 {
+    fn name(&self) -> String {
+        return String::from("CrateMover 9000");
+    }
+
+    fn move_crates(
+        &self,
+        cargo_state: &mut HashMap<usize, Vec<String>>,
+        operation: &CargoOperation,
+    ) {
+        MaxLiftCrane { max_lift: 1 }.move_crates(cargo_state, operation);
+    }
+}
+
+impl CraneModel for CrateMover9001 {
+    fn name(&self) -> String {
+        return String::from("CrateMover 9001");
+    }
+
+    fn move_crates(
+        &self,
+        cargo_state: &mut HashMap<usize, Vec<String>>,
+        operation: &CargoOperation,
+    ) {
+        lift_crates(
+            cargo_state,
+            operation.from,
+            operation.to,
+            operation.quantity,
+        );
+    }
+}
+
+impl CraneModel for MaxLiftCrane {
+    fn name(&self) -> String {
+        return format!("crane lifting up to {} crates", self.max_lift);
+    }
+
+    fn move_crates(
+        &self,
+        cargo_state: &mut HashMap<usize, Vec<String>>,
+        operation: &CargoOperation,
+    ) {
+        let mut remaining = operation.quantity;
+
+        while remaining > 0 {
+            let lift = remaining.min(self.max_lift);
+            lift_crates(cargo_state, operation.from, operation.to, lift);
+            remaining -= lift;
+        }
+    }
+}
+
+fn lift_crates(
+    cargo_state: &mut HashMap<usize, Vec<String>>,
+    from: usize,
+    to: usize,
+    quantity: usize,
+) {
+    /*!
+     * Carry the top `quantity` crates of stack `from` onto stack `to` in a
+     * single lift, keeping their order. Stacks are numbered from 1, as in the
+     * operations.
+     */
+
+    let cargo_from = cargo_state.get_mut(&(from - 1)).unwrap();
+    let cargo_to_move = cargo_from.split_off(cargo_from.len() - quantity);
+
+    let cargo_to = cargo_state.get_mut(&(to - 1)).unwrap();
+    cargo_to.extend(cargo_to_move);
+}
+
+pub fn get_crane_model(name: &str) -> Result<Box<dyn CraneModel>, String> {
+    /*!
+     * Look up a crane model by its command line name: `9000`, `9001`, or
+     * `max-lift:<n>` for a crane lifting up to n crates at a time.
+     */
+
+    if let Some(max_lift) = name.strip_prefix("max-lift:") {
+        return match max_lift.parse::<usize>() {
+            Ok(max_lift) if max_lift > 0 => Ok(Box::new(MaxLiftCrane { max_lift })),
+            _ => Err(format!(
+                "Invalid lift capacity {max_lift:?}: expected a positive number."
+            )),
+        };
+    }
+
+    return match name {
+        "9000" => Ok(Box::new(CrateMover9000)),
+        "9001" => Ok(Box::new(CrateMover9001)),
+        _ => Err(format!(
+            "Unknown crane model {name:?}: expected 9000, 9001 or max-lift:<n>."
+        )),
+    };
+}
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+
+/// A crate, or an explicit `[]` empty slot, found in a row of the drawing.
+struct Cell {
+    /// Character positions of the opening and closing bracket, from 0.
+    first_column: usize,
+    last_column: usize,
+    code: String,
+}
+
+/// A stack number from the label line below the drawing, with the character
+/// positions it spans.
+struct StackLabel {
+    first_column: usize,
+    last_column: usize,
+}
+
+pub fn parse_drawing(lines: &[String]) -> Result<(HashMap<usize, Vec<String>>, usize), String> {
+    /*!
+     * Parse the drawing of the crate stacks at the start of the input, up to
+     * and including the line numbering the stacks. Returns the stacks, keyed
+     * from 0 with the top crate last, and the number of lines read.
+     *
+     * The label line decides how many stacks there are and where they are:
+     * a crate belongs to the stack whose number is written below it. Rows
+     * which mark empty slots with `[]` instead of spaces list one cell per
+     * stack, and are read in order instead.
+     */
+
+    let label_idx = lines
+        .iter()
+        .position(|line| is_label_line(line))
+        .ok_or(String::from(
+            "No stack numbers found: the drawing must end with a line numbering the stacks.",
+        ))?;
+    let labels = parse_labels(&lines[label_idx], label_idx + 1)?;
+
+    let mut cargo_map: HashMap<usize, Vec<String>> = (0..labels.len())
+        .map(|stack_idx| (stack_idx, Vec::new()))
+        .collect();
+
+    // read from the bottom row up, so each crate lands on the ones below it
+    let mut rows_below = 0;
+    for (line_idx, line) in lines[..label_idx].iter().enumerate().rev() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let line_number = line_idx + 1;
+        let cells = parse_cells(line, line_number)?;
+        let has_empty_slots = cells.iter().any(|cell| cell.code.is_empty());
+
+        if has_empty_slots && cells.len() != labels.len() {
+            return Err(format!(
+                "Line {line_number}: a row marking empty slots with [] needs one cell per stack, found {} cells for {} stacks.",
+                cells.len(),
+                labels.len()
+            ));
+        }
+
+        let mut filled_stacks: Vec<usize> = Vec::new();
+        for (cell_idx, cell) in cells.iter().enumerate() {
+            let stack_idx = if has_empty_slots {
+                cell_idx
+            } else {
+                labels
+                    .iter()
+                    .position(|label| {
+                        label.first_column <= cell.last_column
+                            && cell.first_column <= label.last_column
+                    })
+                    .ok_or(format!(
+                        "Line {line_number}, column {}: crate [{}] is not above any stack number.",
+                        cell.first_column + 1,
+                        cell.code
+                    ))?
+            };
+
+            if filled_stacks.contains(&stack_idx) {
+                return Err(format!(
+                    "Line {line_number}, column {}: a second crate was found for stack {}.",
+                    cell.first_column + 1,
+                    stack_idx + 1
+                ));
+            }
+            filled_stacks.push(stack_idx);
+
+            if cell.code.is_empty() {
+                continue;
+            }
+
+            let stack = cargo_map.get_mut(&stack_idx).unwrap();
+            if stack.len() < rows_below {
+                return Err(format!(
+                    "Line {line_number}, column {}: crate [{}] floats above an empty slot in stack {}.",
+                    cell.first_column + 1,
+                    cell.code,
+                    stack_idx + 1
+                ));
+            }
+            stack.push(cell.code.clone());
+        }
+
+        rows_below += 1;
+    }
+
+    return Ok((cargo_map, label_idx + 1));
+}
+
+fn is_label_line(line: &str) -> bool {
+    let mut labels = line.split_whitespace().peekable();
+
+    return labels.peek().is_some() && labels.all(|label| label.parse::<usize>().is_ok());
+}
+
+fn parse_labels(line: &str, line_number: usize) -> Result<Vec<StackLabel>, String> {
+    /*!
+     * Read the stack numbers with their positions, checking that they count
+     * up from 1.
+     */
+
+    let mut labels: Vec<StackLabel> = Vec::new();
+    let chars: Vec<char> = line.chars().collect();
+
+    let mut column = 0;
+    while column < chars.len() {
+        if chars[column].is_whitespace() {
+            column += 1;
+            continue;
+        }
+
+        let first_column = column;
+        while column < chars.len() && !chars[column].is_whitespace() {
+            column += 1;
+        }
+
+        let label: String = chars[first_column..column].iter().collect();
+        let expected = labels.len() + 1;
+        if label.parse::<usize>() != Ok(expected) {
+            return Err(format!(
+                "Line {line_number}, column {}: expected stack number {expected}, found {label:?}.",
+                first_column + 1
+            ));
+        }
+
+        labels.push(StackLabel {
+            first_column,
+            last_column: column - 1,
+        });
+    }
+
+    return Ok(labels);
+}
+
+fn parse_cells(line: &str, line_number: usize) -> Result<Vec<Cell>, String> {
+    /*!
+     * Find the bracketed crates of a row, along with their positions. Anything
+     * but spaces between the crates is an error.
+     */
+
+    let mut cells: Vec<Cell> = Vec::new();
+    let chars: Vec<char> = line.chars().collect();
+
+    let mut column = 0;
+    while column < chars.len() {
+        match chars[column] {
+            '[' => {
+                let last_column = chars[column..]
+                    .iter()
+                    .position(|character| *character == ']')
+                    .map(|offset| column + offset)
+                    .ok_or(format!(
+                        "Line {line_number}, column {}: crate is missing its closing bracket.",
+                        column + 1
+                    ))?;
+
+                let code: String = chars[column + 1..last_column].iter().collect();
+                if code.contains('[') || code.trim() != code {
+                    return Err(format!(
+                        "Line {line_number}, column {}: invalid crate [{code}].",
+                        column + 1
+                    ));
+                }
+
+                cells.push(Cell {
+                    first_column: column,
+                    last_column,
+                    code,
+                });
+                column = last_column + 1;
+            }
+            character if character.is_whitespace() => column += 1,
+            character => {
+                return Err(format!(
+                    "Line {line_number}, column {}: unexpected {character:?} outside of a crate.",
+                    column + 1
+                ));
+            }
+        }
+    }
+
+    return Ok(cells);
+}
+
+pub fn render_drawing(cargo_state: &HashMap<usize, Vec<String>>) -> String {
+    /*!
+     * Draw the stacks in the format of the input, with each crate as `[A]`
+     * and the stack numbers underneath, so that the drawing can be parsed
+     * again by `parse_drawing`.
+     */
+
+    let num_stacks = cargo_state.len();
+    let max_height = cargo_state.values().map(Vec::len).max().unwrap_or(0);
+
+    // wide enough for the longest crate code and the largest stack number
+    let cell_width = cargo_state
+        .values()
+        .flatten()
+        .map(|cargo| cargo.chars().count() + 2)
+        .chain([3, num_stacks.to_string().len()])
+        .max()
+        .unwrap();
+
+    let mut rows: Vec<String> = Vec::new();
+    for level in (0..max_height).rev() {
+        let cells: Vec<String> = (0..num_stacks)
+            .map(|stack_idx| match cargo_state[&stack_idx].get(level) {
+                Some(cargo) => format!("{:^cell_width$}", format!("[{cargo}]")),
+                None => " ".repeat(cell_width),
+            })
+            .collect();
+        rows.push(cells.join(" ").trim_end().to_string());
+    }
+
+    let labels: Vec<String> = (1..=num_stacks)
+        .map(|stack_number| format!("{stack_number:^cell_width$}"))
+        .collect();
+    rows.push(labels.join(" ").trim_end().to_string());
+
+    return rows.join("\n");
+}
@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+
+use crate::crane::CraneModel;
+use crate::drawing;
+use crate::CargoOperation;
+
+/// An operation as it was carried out, with the crates it took off the
+/// `from` stack and the crates it left on the `to` stack, so that it can be
+/// undone and redone whatever crane did the moving.
+struct JournalEntry {
+    operation: CargoOperation,
+    taken: Vec<String>,
+    placed: Vec<String>,
+}
+
+/// The stacks along with every operation carried out on them. The journal
+/// can step back and forth through the operations, and the stacks always
+/// show the state after the current step.
+pub struct Journal {
+    cargo_state: HashMap<usize, Vec<String>>,
+    entries: Vec<JournalEntry>,
+    /// Number of operations currently applied to the stacks.
+    step: usize,
+}
+
+impl Journal {
+    pub fn new(initial_cargo_state: HashMap<usize, Vec<String>>) -> Journal {
+        return Journal {
+            cargo_state: initial_cargo_state,
+            entries: Vec::new(),
+            step: 0,
+        };
+    }
+
+    pub fn cargo_state(&self) -> &HashMap<usize, Vec<String>> {
+        return &self.cargo_state;
+    }
+
+    pub fn step(&self) -> usize {
+        return self.step;
+    }
+
+    pub fn len(&self) -> usize {
+        return self.entries.len();
+    }
+
+    pub fn record(&mut self, operation: CargoOperation, crane_model: &dyn CraneModel) {
+        /*!
+         * Carry out a checked operation with the given crane. Any undone
+         * operations are dropped, as they no longer follow on from the stacks.
+         */
+
+        self.entries.truncate(self.step);
+
+        let cargo_from = &self.cargo_state[&(operation.from - 1)];
+        let taken = cargo_from[cargo_from.len() - operation.quantity..].to_vec();
+
+        crane_model.move_crates(&mut self.cargo_state, &operation);
+
+        let cargo_to = &self.cargo_state[&(operation.to - 1)];
+        let placed = cargo_to[cargo_to.len() - operation.quantity..].to_vec();
+
+        self.entries.push(JournalEntry {
+            operation,
+            taken,
+            placed,
+        });
+        self.step += 1;
+    }
+
+    pub fn undo(&mut self) -> bool {
+        /*!
+         * Take back the last applied operation. Returns false if there is
+         * none.
+         */
+
+        if self.step == 0 {
+            return false;
+        }
+        self.step -= 1;
+
+        let entry = &self.entries[self.step];
+        let cargo_to = self.cargo_state.get_mut(&(entry.operation.to - 1)).unwrap();
+        cargo_to.truncate(cargo_to.len() - entry.placed.len());
+        let cargo_from = self
+            .cargo_state
+            .get_mut(&(entry.operation.from - 1))
+            .unwrap();
+        cargo_from.extend(entry.taken.iter().cloned());
+
+        return true;
+    }
+
+    pub fn redo(&mut self) -> bool {
+        /*!
+         * Apply the next undone operation again. Returns false if there is
+         * none.
+         */
+
+        let Some(entry) = self.entries.get(self.step) else {
+            return false;
+        };
+
+        let cargo_from = self
+            .cargo_state
+            .get_mut(&(entry.operation.from - 1))
+            .unwrap();
+        cargo_from.truncate(cargo_from.len() - entry.taken.len());
+        let cargo_to = self.cargo_state.get_mut(&(entry.operation.to - 1)).unwrap();
+        cargo_to.extend(entry.placed.iter().cloned());
+
+        self.step += 1;
+
+        return true;
+    }
+
+    pub fn jump_to(&mut self, step: usize) -> Result<(), String> {
+        /*!
+         * Undo or redo operations until the stacks show the state after the
+         * given step, with step 0 being the initial stacks.
+         */
+
+        if step > self.entries.len() {
+            return Err(format!(
+                "Cannot jump to step {step}, there are only {} operations.",
+                self.entries.len()
+            ));
+        }
+
+        while self.step > step {
+            self.undo();
+        }
+        while self.step < step {
+            self.redo();
+        }
+
+        return Ok(());
+    }
+
+    pub fn export(&self) -> String {
+        /*!
+         * Write out the stacks at the current step in the format of the input,
+         * followed by the operations still to come, so that the simulation
+         * can be resumed from this point.
+         */
+
+        let remaining_operations: Vec<String> = self.entries[self.step..]
+            .iter()
+            .map(|entry| format!("{}\n", entry.operation))
+            .collect();
+
+        return format!(
+            "{}\n\n{}",
+            drawing::render_drawing(&self.cargo_state),
+            remaining_operations.concat()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crane::{CrateMover9000, CrateMover9001, MaxLiftCrane};
+
+    type CargoState = HashMap<usize, Vec<String>>;
+
+    fn get_stacks(stacks: &[&[&str]]) -> CargoState {
+        return stacks
+            .iter()
+            .enumerate()
+            .map(|(stack_idx, stack)| (stack_idx, stack.iter().map(|c| c.to_string()).collect()))
+            .collect();
+    }
+
+    fn get_operation(quantity: usize, from: usize, to: usize) -> CargoOperation {
+        return CargoOperation {
+            quantity,
+            from,
+            to,
+            line_number: 0,
+        };
+    }
+
+    #[test]
+    fn undo_and_redo_restore_the_stacks_for_every_crane() {
+        let initial_stacks = get_stacks(&[&["A", "B", "C", "D"], &["E"], &[]]);
+        let operations = [
+            get_operation(3, 1, 3),
+            get_operation(2, 3, 3),
+            get_operation(1, 2, 1),
+        ];
+        let cranes: [(Box<dyn CraneModel>, CargoState); 3] = [
+            (
+                Box::new(CrateMover9000),
+                get_stacks(&[&["A", "E"], &[], &["D", "C", "B"]]),
+            ),
+            (
+                Box::new(CrateMover9001),
+                get_stacks(&[&["A", "E"], &[], &["B", "C", "D"]]),
+            ),
+            (
+                Box::new(MaxLiftCrane { max_lift: 2 }),
+                get_stacks(&[&["A", "E"], &[], &["C", "D", "B"]]),
+            ),
+        ];
+
+        for (crane_model, final_stacks) in cranes {
+            let mut journal = Journal::new(initial_stacks.clone());
+            for operation in operations {
+                journal.record(operation, crane_model.as_ref());
+            }
+            assert_eq!(
+                journal.cargo_state(),
+                &final_stacks,
+                "{}",
+                crane_model.name()
+            );
+
+            while journal.undo() {}
+            assert_eq!(journal.step(), 0);
+            assert_eq!(
+                journal.cargo_state(),
+                &initial_stacks,
+                "{}",
+                crane_model.name()
+            );
+
+            while journal.redo() {}
+            assert_eq!(journal.step(), operations.len());
+            assert_eq!(
+                journal.cargo_state(),
+                &final_stacks,
+                "{}",
+                crane_model.name()
+            );
+
+            journal.jump_to(0).unwrap();
+            assert_eq!(
+                journal.cargo_state(),
+                &initial_stacks,
+                "{}",
+                crane_model.name()
+            );
+
+            journal.jump_to(journal.len()).unwrap();
+            assert_eq!(
+                journal.cargo_state(),
+                &final_stacks,
+                "{}",
+                crane_model.name()
+            );
+            assert!(journal.jump_to(journal.len() + 1).is_err());
+        }
+    }
+}
@@ -1,166 +1,344 @@
+#![allow(clippy::needless_return)]
+
+mod crane;
+mod drawing;
+mod journal;
+mod trace;
+mod validation;
+
 use regex::Regex;
 use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::fs;
 use std::fs::File;
 use std::io::{self, BufRead, BufReader, Lines};
+use std::time::Duration;
+
+use crane::{CraneModel, CrateMover9000, CrateMover9001};
+use journal::Journal;
+use trace::TraceMode;
+use validation::OperationCheck;
 
 fn main() {
-    let file_reader = read_data_file("data/data.txt").unwrap();
-
-    let mut cargo_composition_lines: Vec<String> = Vec::new();
-    let mut cargo_operation_lines: Vec<String> = Vec::new();
-
-    let mut processing_operations = false;
-    for line in file_reader {
-        /*
-         * flag to be switched when iteration reaches end of cargo processing and
-         * moves onto cargo operations (movements) processing
-         */
-
-        if let Ok(ln) = line {
-            if ln.split_whitespace().collect::<String>() == String::from("123456789") {
-                processing_operations = true;
-                continue;
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let lines: Vec<String> = read_data_file("data/data.txt")
+        .unwrap()
+        .map_while(Result::ok)
+        .collect();
+
+    let (cargo_composition, num_drawing_lines) = match drawing::parse_drawing(&lines) {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            eprintln!("{message}");
+            std::process::exit(1);
+        }
+    };
+
+    let cargo_operation_lines: Vec<(usize, String)> = lines
+        .iter()
+        .enumerate()
+        .skip(num_drawing_lines)
+        .filter(|(_line_idx, line)| !line.is_empty())
+        .map(|(line_idx, line)| (line_idx + 1, line.clone()))
+        .collect();
+    let cargo_operations = match load_cargo_operations(cargo_operation_lines) {
+        Ok(cargo_operations) => cargo_operations,
+        Err(message) => {
+            eprintln!("{message}");
+            std::process::exit(1);
+        }
+    };
+
+    let lenient = args.iter().any(|arg| arg == "--lenient");
+    let trace_mode = match get_trace_mode(&args) {
+        Ok(trace_mode) => trace_mode,
+        Err(message) => {
+            eprintln!("{message}");
+            std::process::exit(1);
+        }
+    };
+
+    // run the chosen crane, or compare the CrateMover 9000 and 9001
+    let crane_models: Vec<Box<dyn CraneModel>> = match get_option_value(&args, "--crane") {
+        Some(name) => match crane::get_crane_model(name) {
+            Ok(crane_model) => vec![crane_model],
+            Err(message) => {
+                eprintln!("{message}");
+                std::process::exit(1);
             }
-            if ln == String::from("") {
-                continue;
+        },
+        None => vec![Box::new(CrateMover9000), Box::new(CrateMover9001)],
+    };
+
+    let until_step = get_option_value(&args, "--until").map(|value| {
+        value.parse::<usize>().unwrap_or_else(|_| {
+            eprintln!("Invalid value for --until: {value}.");
+            std::process::exit(1);
+        })
+    });
+    let export_file = get_option_value(&args, "--export");
+    if export_file.is_some() && crane_models.len() > 1 {
+        eprintln!("--export writes the stacks of a single crane, choose one with --crane.");
+        std::process::exit(1);
+    }
+
+    for crane_model in crane_models {
+        let crane_name = crane_model.name();
+        let num_steps = cargo_operations.len();
+        let mut show_step =
+            |step: usize,
+             operation: Option<&CargoOperation>,
+             cargo_state: &HashMap<usize, Vec<String>>| {
+                if let Some(trace_mode) = &trace_mode {
+                    trace::show_step(
+                        trace_mode,
+                        &crane_name,
+                        step,
+                        num_steps,
+                        operation,
+                        cargo_state,
+                    );
+                }
+            };
+
+        let (mut journal, clamped_operations) = match execute_cargo_operations(
+            &cargo_composition,
+            &cargo_operations,
+            crane_model.as_ref(),
+            lenient,
+            &mut show_step,
+        ) {
+            Ok(executed) => executed,
+            Err(message) => {
+                eprintln!("{message}");
+                std::process::exit(1);
             }
+        };
 
-            if processing_operations {
-                cargo_operation_lines.push(ln);
+        // the stack heights do not depend on the crane, so one run is
+        // enough to validate the operations
+        if args.iter().any(|arg| arg == "--dry-run") {
+            if clamped_operations.is_empty() {
+                println!("All {} operations are valid.", cargo_operations.len());
             } else {
-                cargo_composition_lines.push(ln);
+                println!(
+                    "{} of {} operations ask for more crates than the stack holds, and move fewer in lenient mode:",
+                    clamped_operations.len(),
+                    cargo_operations.len()
+                );
+                for clamped_operation in clamped_operations {
+                    println!("    {clamped_operation}");
+                }
             }
+            return;
         }
-    }
 
-    let mut cargo_composition = load_cargo_composition(cargo_composition_lines);
-    let mut cargo_operations = load_cargo_operations(cargo_operation_lines);
-
-    let cargo_final_state = execute_cargo_operations(cargo_composition, cargo_operations);
+        if let Some(until_step) = until_step {
+            if let Err(message) = journal.jump_to(until_step) {
+                eprintln!("{message}");
+                std::process::exit(1);
+            }
+        }
 
-    let top_cargo = get_top_cargo(cargo_final_state);
-    println!("{top_cargo}");
-}
+        let top_cargo = get_top_cargo(journal.cargo_state());
+        if journal.step() == journal.len() {
+            println!("{}: {top_cargo}", crane_model.name());
+        } else {
+            println!(
+                "{} after step {}/{}: {top_cargo}",
+                crane_model.name(),
+                journal.step(),
+                journal.len()
+            );
+        }
 
-fn read_data_file(filename: &str) -> io::Result<Lines<BufReader<File>>> {
-    let file = File::open::<&str>(filename).unwrap();
-    return Ok(BufReader::new(file).lines());
+        if let Some(export_file) = export_file {
+            fs::write(export_file, journal.export()).unwrap();
+            println!(
+                "Stacks after step {} written to {export_file}.",
+                journal.step()
+            );
+        }
+    }
 }
 
-fn load_cargo_composition(cargo_lines: Vec<String>) -> HashMap<usize, Vec<String>> {
+fn get_option_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
     /*!
-     * Read the data file and parse out the crate stack composition
+     * Fetch the value following the given flag in the command line arguments.
      */
 
-    let mut cargo_map: HashMap<usize, Vec<String>> = HashMap::new();
+    let flag_idx = args.iter().position(|arg| arg == flag)?;
 
-    for line in cargo_lines.iter() {
-        let cargo_row = line.split(' ');
-
-        for (cargo_idx, cargo) in cargo_row.enumerate() {
-            let cargo = cargo.replace("[", "").replace("]", "");
+    return args.get(flag_idx + 1).map(String::as_str);
+}
 
-            if cargo == String::from("") {
-                continue;
-            }
+fn get_trace_mode(args: &[String]) -> Result<Option<TraceMode>, String> {
+    /*!
+     * Choose how to show the operations: `--trace` prints the stacks after
+     * each one, `--animate` redraws them in place at `--speed` operations per
+     * second (5 by default). Without either, only the result is shown.
+     */
 
-            if let Some(cargo_stack) = cargo_map.get_mut(&cargo_idx) {
-                cargo_stack.push(cargo);
-            } else {
-                cargo_map.insert(cargo_idx, vec![cargo]);
-            }
-        }
+    if args.iter().any(|arg| arg == "--animate") {
+        let speed = match get_option_value(args, "--speed") {
+            Some(speed) => match speed.parse::<f64>() {
+                Ok(speed) if speed > 0.0 && speed.is_finite() => speed,
+                _ => {
+                    return Err(format!(
+                        "Invalid value for --speed: {speed}, expected a positive number."
+                    ))
+                }
+            },
+            None => 5.0,
+        };
+
+        return Ok(Some(TraceMode::Animate {
+            frame_delay: Duration::from_secs_f64(1.0 / speed),
+        }));
     }
 
-    // reverse the vec so that the top cargo is at the end
-    for (_stack_idx, stack) in &mut cargo_map {
-        stack.reverse()
+    if args.iter().any(|arg| arg == "--trace") {
+        return Ok(Some(TraceMode::Print));
     }
 
-    return cargo_map;
+    return Ok(None);
+}
+
+fn read_data_file(filename: &str) -> io::Result<Lines<BufReader<File>>> {
+    let file = File::open::<&str>(filename).unwrap();
+    return Ok(BufReader::new(file).lines());
 }
 
-fn load_cargo_operations(operation_lines: Vec<String>) -> Vec<CargoOperation> {
+fn load_cargo_operations(
+    operation_lines: Vec<(usize, String)>,
+) -> Result<Vec<CargoOperation>, String> {
     /*!
-     * Read the data file and parse the order of cargo operations.
+     * Read the data file and parse the order of cargo operations. Each line
+     * comes with its line number, which is kept to report invalid operations.
      */
 
     let mut cargo_operations: Vec<CargoOperation> = Vec::new();
 
-    let parse_operations = Regex::new(r"move (\d+) from (\d+) to (\d+)").unwrap();
+    let parse_operations = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
 
-    for operation in operation_lines {
-        let operation_parsed = parse_operations.captures(operation.as_str()).unwrap();
-        cargo_operations.push(CargoOperation {
-            quantity: operation_parsed
-                .get(1)
-                .unwrap()
-                .as_str()
-                .parse::<usize>()
-                .unwrap(),
-            from: operation_parsed
-                .get(2)
-                .unwrap()
-                .as_str()
-                .parse::<usize>()
-                .unwrap(),
-            to: operation_parsed
-                .get(3)
-                .unwrap()
-                .as_str()
+    for (line_number, operation) in operation_lines {
+        let operation_parsed = parse_operations
+            .captures(operation.trim())
+            .ok_or(format!(
+                "Line {line_number}: invalid operation {operation:?}, expected \"move <n> from <n> to <n>\"."
+            ))?;
+
+        let parse_number = |group_idx: usize| {
+            let number = operation_parsed.get(group_idx).unwrap().as_str();
+            number
                 .parse::<usize>()
-                .unwrap(),
+                .map_err(|error| format!("Line {line_number}: invalid number {number}: {error}."))
+        };
+
+        cargo_operations.push(CargoOperation {
+            quantity: parse_number(1)?,
+            from: parse_number(2)?,
+            to: parse_number(3)?,
+            line_number,
         })
     }
 
-    return cargo_operations;
+    return Ok(cargo_operations);
 }
 
 fn execute_cargo_operations(
-    inital_cargo_state: HashMap<usize, Vec<String>>,
-    cargo_operations: Vec<CargoOperation>,
-) -> HashMap<usize, Vec<String>> {
+    inital_cargo_state: &HashMap<usize, Vec<String>>,
+    cargo_operations: &[CargoOperation],
+    crane_model: &dyn CraneModel,
+    lenient: bool,
+    show_step: &mut StepObserver<'_>,
+) -> Result<(Journal, Vec<String>), String> {
     /*!
-     * Take the initial cargo state and the cargo operations, and return the
-     * end state after the operations have been conducted.
+     * Take the initial cargo state and the cargo operations, and return a
+     * journal of the given crane model conducting the operations, ending at
+     * the final state. Each operation is checked before it is carried out,
+     * and the first one which cannot be is reported along with the stacks at
+     * that point. In lenient mode, the operations which moved fewer crates
+     * than asked for are described along with the journal. `show_step` is
+     * given the stacks initially and after every operation.
      */
-    let mut cargo_state = inital_cargo_state.clone();
-
-    for operation in cargo_operations {
-        let cargo_from = cargo_state.get_mut(&(operation.from - 1)).unwrap();
-        let cargo_from_split = cargo_from.split_at(cargo_from.len() - operation.quantity);
-
-        let cargo_to_keep = cargo_from_split.0.to_vec();
-        let mut cargo_to_move = cargo_from_split.1.to_vec();
-
-        cargo_from.clear();
-        cargo_from.extend(cargo_to_keep);
-
-        let cargo_to = cargo_state.get_mut(&(operation.to - 1)).unwrap();
-        cargo_to.extend(cargo_to_move);
+    let mut journal = Journal::new(inital_cargo_state.clone());
+    let mut clamped_operations: Vec<String> = Vec::new();
+    show_step(0, None, journal.cargo_state());
+
+    for (operation_idx, operation) in cargo_operations.iter().enumerate() {
+        match validation::check_cargo_operation(journal.cargo_state(), operation, lenient) {
+            Ok(check) => {
+                let quantity = match check {
+                    OperationCheck::Valid => operation.quantity,
+                    OperationCheck::Clamped { available } => {
+                        clamped_operations.push(format!(
+                            "line {}: {operation}, stack {} only holds {available}",
+                            operation.line_number, operation.from
+                        ));
+                        available
+                    }
+                };
+                let operation = CargoOperation {
+                    quantity,
+                    ..*operation
+                };
+                journal.record(operation, crane_model);
+                show_step(operation_idx + 1, Some(&operation), journal.cargo_state());
+            }
+            Err(message) => {
+                return Err(format!(
+                    "Line {}: {message}\nStacks before the operation, using the {}:\n{}",
+                    operation.line_number,
+                    crane_model.name(),
+                    validation::describe_stacks(journal.cargo_state())
+                ));
+            }
+        }
     }
 
-    return cargo_state;
+    return Ok((journal, clamped_operations));
 }
 
-fn get_top_cargo(cargo_state: HashMap<usize, Vec<String>>) -> String {
+fn get_top_cargo(cargo_state: &HashMap<usize, Vec<String>>) -> String {
     /*!
-     * Get the codes for the top crate of each stack.
+     * Get the codes for the top crate of each stack. Empty stacks are
+     * skipped.
      */
 
     let mut top_cargo = String::from("");
 
     for key in 0..cargo_state.len() {
-        let last_elem = cargo_state.get(&key).unwrap().last().unwrap();
-        top_cargo.insert_str(top_cargo.len(), last_elem.to_owned().as_str());
+        if let Some(last_elem) = cargo_state.get(&key).unwrap().last() {
+            top_cargo.insert_str(top_cargo.len(), last_elem.to_owned().as_str());
+        }
     }
 
     return top_cargo;
 }
 
-#[derive(Debug)]
-struct CargoOperation {
+/// Called with the step number, the operation carried out, and the stacks
+/// after it.
+type StepObserver<'a> =
+    dyn FnMut(usize, Option<&CargoOperation>, &HashMap<usize, Vec<String>>) + 'a;
+
+#[derive(Debug, Clone, Copy)]
+pub struct CargoOperation {
     quantity: usize,
     from: usize,
     to: usize,
+    /// Line of the input the operation was read from.
+    line_number: usize,
+}
+
+impl fmt::Display for CargoOperation {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        return write!(
+            formatter,
+            "move {} from {} to {}",
+            self.quantity, self.from, self.to
+        );
+    }
 }
@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+
+use crate::drawing;
+use crate::CargoOperation;
+
+/// How the stacks are shown while the operations are carried out.
+pub enum TraceMode {
+    /// Print the drawing after every operation, one after the other.
+    Print,
+    /// Redraw the drawing in place, waiting `frame_delay` between operations.
+    Animate { frame_delay: Duration },
+}
+
+pub fn show_step(
+    trace_mode: &TraceMode,
+    crane_name: &str,
+    step: usize,
+    num_steps: usize,
+    operation: Option<&CargoOperation>,
+    cargo_state: &HashMap<usize, Vec<String>>,
+) {
+    /*!
+     * Show the stacks after the given step, the operation which led to it,
+     * and how far through the operations the crane is. Step 0 is the initial
+     * drawing, before any operation.
+     */
+
+    let heading = match operation {
+        Some(operation) => format!(
+            "{crane_name}, step {step}/{num_steps}, line {}: {operation}",
+            operation.line_number
+        ),
+        None => format!("{crane_name}, step 0/{num_steps}: initial stacks"),
+    };
+
+    match trace_mode {
+        TraceMode::Print => {
+            println!("{heading}");
+            println!("{}", drawing::render_drawing(cargo_state));
+            println!();
+        }
+        TraceMode::Animate { frame_delay } => {
+            // clear the terminal and move the cursor back to the top
+            print!("\x1b[2J\x1b[H");
+            println!("{heading}");
+            println!();
+            println!("{}", drawing::render_drawing(cargo_state));
+            thread::sleep(*frame_delay);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use crate::CargoOperation;
+
+/// The outcome of checking an operation which can be carried out.
+pub enum OperationCheck {
+    Valid,
+    /// The stack holds fewer crates than asked for, so in lenient mode only
+    /// the `available` crates are moved.
+    Clamped {
+        available: usize,
+    },
+}
+
+pub fn check_cargo_operation(
+    cargo_state: &HashMap<usize, Vec<String>>,
+    operation: &CargoOperation,
+    lenient: bool,
+) -> Result<OperationCheck, String> {
+    /*!
+     * Check that an operation can be carried out on the current stacks. In
+     * lenient mode, an operation asking for more crates than the stack holds
+     * is clamped to move all of them instead.
+     */
+
+    for stack_number in [operation.from, operation.to] {
+        if !(1..=cargo_state.len()).contains(&stack_number) {
+            return Err(format!(
+                "there is no stack {stack_number}, the stacks are numbered 1 to {}.",
+                cargo_state.len()
+            ));
+        }
+    }
+
+    let available = cargo_state[&(operation.from - 1)].len();
+    if operation.quantity > available {
+        if lenient {
+            return Ok(OperationCheck::Clamped { available });
+        }
+
+        return Err(format!(
+            "cannot move {} crates from stack {}, it only holds {available}.",
+            operation.quantity, operation.from
+        ));
+    }
+
+    return Ok(OperationCheck::Valid);
+}
+
+pub fn describe_stacks(cargo_state: &HashMap<usize, Vec<String>>) -> String {
+    /*!
+     * List the crates of each stack from the bottom up, one stack per line.
+     */
+
+    let mut stack_lines: Vec<String> = Vec::new();
+
+    for stack_idx in 0..cargo_state.len() {
+        let crates: Vec<String> = cargo_state[&stack_idx]
+            .iter()
+            .map(|cargo| format!("[{cargo}]"))
+            .collect();
+        stack_lines.push(format!("    stack {}: {}", stack_idx + 1, crates.join(" ")));
+    }
+
+    return stack_lines.join("\n");
+}